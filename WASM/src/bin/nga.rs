@@ -0,0 +1,443 @@
+//! `nga` - Command-line batch converter for Agentforce agent definitions
+//!
+//! Converts Salesforce Agentforce JSON/YAML exports into NGA Agent Script files
+//! using the same conversion pipeline as the WebAssembly module.
+//!
+//! Exit codes:
+//! - `0` - every input converted cleanly
+//! - `1` - every input converted, but at least one produced conversion warnings
+//! - `2` - at least one input could not be read, parsed or converted, or two inputs
+//!   would write the same output file
+//! - `64` - invalid command-line usage
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use nga_converter::models::{AgentforceInput, ConversionRules};
+use nga_converter::report_generator::ReportMetadata;
+use nga_converter::{
+    convert, generate_report, parse_id_map, parse_input_json,
+    parse_input_yaml, parse_rules, ConversionOutcome, ConvertError, IdMap,
+};
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+const EXIT_OK: u8 = 0;
+const EXIT_WARNINGS: u8 = 1;
+const EXIT_ERRORS: u8 = 2;
+const EXIT_USAGE: u8 = 64;
+
+/// Suffix appended to the input file stem for generated Agent Script files
+const OUTPUT_SUFFIX: &str = ".nga.yaml";
+
+/// Suffix appended to the input file stem for generated report files
+const REPORT_SUFFIX: &str = ".report.json";
+
+const USAGE: &str = "\
+Usage: nga [OPTIONS] <INPUT>...
+
+Convert Agentforce JSON/YAML agent definitions to NGA Agent Script.
+
+Arguments:
+  <INPUT>...             Input files, or directories containing .json/.yaml/.yml files
+
+Options:
+  -r, --rules <FILE>     Conversion rules JSON (e.g. website/nga-rules.json)
+  -o, --out-dir <DIR>    Directory for generated files (default: next to each input)
+      --id-map <FILE>    Record ID to API name table (CSV or JSON) for flow/Apex targets
+      --report           Also write a <name>.report.json conversion report
+  -h, --help             Print this help
+  -V, --version          Print version
+
+Exit codes: 0 success, 1 conversion warnings, 2 parse/conversion errors or output name collisions, 64 usage error";
+
+// ============================================================================
+// ARGUMENT PARSING
+// ============================================================================
+
+/// Parsed command-line options
+#[derive(Debug, Default)]
+struct CliOptions {
+    inputs: Vec<PathBuf>,
+    rules_path: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    id_map_path: Option<PathBuf>,
+    write_report: bool,
+}
+
+/// What the command line asked us to do
+enum Command {
+    Convert(CliOptions),
+    Help,
+    Version,
+}
+
+/// Parse command-line arguments (without the program name)
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = CliOptions::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--report" => options.write_report = true,
+            "-r" | "--rules" => {
+                let value = args.next().ok_or("missing value for --rules")?;
+                options.rules_path = Some(PathBuf::from(value));
+            }
+            "-o" | "--out-dir" => {
+                let value = args.next().ok_or("missing value for --out-dir")?;
+                options.out_dir = Some(PathBuf::from(value));
+            }
+            "--id-map" => {
+                let value = args.next().ok_or("missing value for --id-map")?;
+                options.id_map_path = Some(PathBuf::from(value));
+            }
+            _ if arg.starts_with("--rules=") => {
+                options.rules_path = Some(PathBuf::from(&arg["--rules=".len()..]));
+            }
+            _ if arg.starts_with("--out-dir=") => {
+                options.out_dir = Some(PathBuf::from(&arg["--out-dir=".len()..]));
+            }
+            _ if arg.starts_with("--id-map=") => {
+                options.id_map_path = Some(PathBuf::from(&arg["--id-map=".len()..]));
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option '{}'", arg));
+            }
+            _ => options.inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.inputs.is_empty() {
+        return Err("no input files given".to_string());
+    }
+
+    Ok(Command::Convert(options))
+}
+
+// ============================================================================
+// INPUT DISCOVERY
+// ============================================================================
+
+/// Check if a path looks like an agent definition we can convert
+fn is_convertible_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+    // Skip files this tool generated on a previous run
+    if file_name.ends_with(OUTPUT_SUFFIX) || file_name.ends_with(REPORT_SUFFIX) {
+        return false;
+    }
+
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+        Some("json" | "yaml" | "yml")
+    )
+}
+
+/// Expand input arguments into a sorted list of files
+/// Directories contribute their top-level .json/.yaml/.yml files
+fn collect_input_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        if input.is_dir() {
+            let entries = fs::read_dir(input)
+                .map_err(|e| format!("{}: failed to read directory: {}", input.display(), e))?;
+            let mut dir_files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && is_convertible_file(path))
+                .collect();
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(input.clone());
+        }
+    }
+
+    Ok(files)
+}
+
+// ============================================================================
+// CONVERSION
+// ============================================================================
+
+/// Outcome of converting a single input file
+enum FileOutcome {
+    Converted,
+    ConvertedWithWarnings,
+}
+
+/// Load conversion rules from a JSON file
+fn load_rules(path: &Path) -> Result<Option<ConversionRules>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("{}: failed to read rules: {}", path.display(), e))?;
+    parse_rules(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Load a record ID resolution table from a CSV or JSON file
+fn load_id_map(path: &Path) -> Result<IdMap, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("{}: failed to read ID map: {}", path.display(), e))?;
+    parse_id_map(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Check if a path has a YAML extension
+fn is_yaml_path(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+        Some("yaml" | "yml")
+    )
+}
+
+/// Parse and convert input text as JSON or YAML depending on the file extension
+/// Record IDs found in the ID map are rewritten to API names during conversion
+fn convert_input(
+    path: &Path,
+    text: &str,
+    rules: Option<&ConversionRules>,
+    id_map: Option<&IdMap>,
+) -> Result<(AgentforceInput, ConversionOutcome, &'static str), ConvertError> {
+    let (input, input_format) = if is_yaml_path(path) {
+        (parse_input_yaml(text)?, "YAML")
+    } else {
+        (parse_input_json(text)?, "JSON")
+    };
+    let outcome = convert(&input, rules, id_map);
+    Ok((input, outcome, input_format))
+}
+
+/// Build the path of a generated file for the given input
+fn output_path_for(input: &Path, out_dir: Option<&Path>, suffix: &str) -> PathBuf {
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("agent");
+    let dir = out_dir
+        .map(Path::to_path_buf)
+        .or_else(|| input.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    dir.join(format!("{}{}", stem, suffix))
+}
+
+/// Find two inputs that would write the same output file, e.g. `agent.json` and `agent.yaml`
+fn find_output_collision(files: &[PathBuf], out_dir: Option<&Path>) -> Option<String> {
+    let mut seen: HashMap<PathBuf, &Path> = HashMap::new();
+    for file in files {
+        let output_path = output_path_for(file, out_dir, OUTPUT_SUFFIX);
+        if let Some(previous) = seen.get(&output_path) {
+            return Some(format!(
+                "{} and {} would both be written to {}",
+                previous.display(), file.display(), output_path.display()
+            ));
+        }
+        seen.insert(output_path, file);
+    }
+    None
+}
+
+/// Convert one input file, writing the Agent Script (and optionally the report)
+fn convert_file(
+    path: &Path,
+    rules: Option<&ConversionRules>,
+    id_map: Option<&IdMap>,
+    options: &CliOptions,
+) -> Result<FileOutcome, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read input: {}", e))?;
+    let (input, outcome, input_format) =
+        convert_input(path, &text, rules, id_map).map_err(|e| e.to_string())?;
+
+    let output_path = output_path_for(path, options.out_dir.as_deref(), OUTPUT_SUFFIX);
+    fs::write(&output_path, &outcome.yaml)
+        .map_err(|e| format!("{}: failed to write output: {}", output_path.display(), e))?;
+    println!("{} -> {}", path.display(), output_path.display());
+
+    if options.write_report {
+        let metadata = ReportMetadata {
+            input_format: input_format.to_string(),
+            topic_count: outcome.topic_count,
+            action_count: outcome.action_count,
+            has_variables_with_dollar: outcome.has_variables_with_dollar,
+            alert_message: outcome.has_variables_with_dollar.then(|| outcome.alert_message.clone()),
+            status_suffix: outcome.has_variables_with_dollar.then(|| outcome.status_suffix.clone()),
+            warnings: outcome.warnings.clone(),
+            renames: outcome.renames.clone(),
+        };
+        let report_data = generate_report(&input, &outcome.yaml, &metadata, id_map);
+        let report_json = serde_json::to_string_pretty(&report_data)
+            .map_err(|e| format!("failed to serialize report data: {}", e))?;
+
+        let report_path = output_path_for(path, options.out_dir.as_deref(), REPORT_SUFFIX);
+        fs::write(&report_path, report_json)
+            .map_err(|e| format!("{}: failed to write report: {}", report_path.display(), e))?;
+        println!("{} -> {}", path.display(), report_path.display());
+    }
+
+    for warning in &outcome.warnings {
+        eprintln!("warning: {}: {} (at {})", path.display(), warning.message, warning.path);
+    }
+    for rename in &outcome.renames {
+        eprintln!(
+            "warning: {}: renamed '{}' to '{}' (at {})",
+            path.display(), rename.original, rename.name, rename.path
+        );
+    }
+    if outcome.has_variables_with_dollar {
+        eprintln!("warning: {}: {}", path.display(), outcome.alert_message);
+    }
+    if outcome.has_variables_with_dollar || !outcome.warnings.is_empty() || !outcome.renames.is_empty() {
+        return Ok(FileOutcome::ConvertedWithWarnings);
+    }
+
+    Ok(FileOutcome::Converted)
+}
+
+/// Run a batch conversion and return the process exit code
+fn run(options: CliOptions) -> u8 {
+    let rules = match options.rules_path.as_deref().map(load_rules).transpose() {
+        Ok(rules) => rules.flatten(),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_ERRORS;
+        }
+    };
+
+    let id_map = match options.id_map_path.as_deref().map(load_id_map).transpose() {
+        Ok(id_map) => id_map,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_ERRORS;
+        }
+    };
+
+    if let Some(out_dir) = &options.out_dir {
+        if let Err(e) = fs::create_dir_all(out_dir) {
+            eprintln!("error: {}: failed to create output directory: {}", out_dir.display(), e);
+            return EXIT_ERRORS;
+        }
+    }
+
+    let files = match collect_input_files(&options.inputs) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_ERRORS;
+        }
+    };
+
+    if let Some(collision) = find_output_collision(&files, options.out_dir.as_deref()) {
+        eprintln!("error: {}", collision);
+        return EXIT_ERRORS;
+    }
+
+    let mut exit_code = EXIT_OK;
+    for file in &files {
+        match convert_file(file, rules.as_ref(), id_map.as_ref(), &options) {
+            Ok(FileOutcome::Converted) => {}
+            Ok(FileOutcome::ConvertedWithWarnings) => exit_code = exit_code.max(EXIT_WARNINGS),
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                exit_code = EXIT_ERRORS;
+            }
+        }
+    }
+
+    exit_code
+}
+
+fn main() -> ExitCode {
+    let code = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Convert(options)) => run(options),
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        Ok(Command::Version) => {
+            println!("nga {}", env!("CARGO_PKG_VERSION"));
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            EXIT_USAGE
+        }
+    };
+
+    ExitCode::from(code)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_collects_inputs_and_options() {
+        let command = parse_args(args(&["-r", "rules.json", "--report", "--out-dir=out", "--id-map", "ids.csv", "a.json", "dir"])).unwrap();
+        let Command::Convert(options) = command else {
+            panic!("Expected a convert command");
+        };
+        assert_eq!(options.rules_path, Some(PathBuf::from("rules.json")));
+        assert_eq!(options.out_dir, Some(PathBuf::from("out")));
+        assert_eq!(options.id_map_path, Some(PathBuf::from("ids.csv")));
+        assert!(options.write_report);
+        assert_eq!(options.inputs, vec![PathBuf::from("a.json"), PathBuf::from("dir")]);
+    }
+
+    #[test]
+    fn test_parse_args_rejects_bad_usage() {
+        assert!(parse_args(args(&[])).is_err());
+        assert!(parse_args(args(&["--rules"])).is_err());
+        assert!(parse_args(args(&["a.json", "--id-map"])).is_err());
+        assert!(parse_args(args(&["--bogus", "a.json"])).is_err());
+    }
+
+    #[test]
+    fn test_is_convertible_file_skips_generated_files() {
+        assert!(is_convertible_file(Path::new("agent.json")));
+        assert!(is_convertible_file(Path::new("agent.YAML")));
+        assert!(is_convertible_file(Path::new("agent.yml")));
+        assert!(!is_convertible_file(Path::new("agent.nga.yaml")));
+        assert!(!is_convertible_file(Path::new("agent.report.json")));
+        assert!(!is_convertible_file(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn test_output_path_for() {
+        assert_eq!(
+            output_path_for(Path::new("in/agent.json"), None, OUTPUT_SUFFIX),
+            PathBuf::from("in/agent.nga.yaml")
+        );
+        assert_eq!(
+            output_path_for(Path::new("in/agent.json"), Some(Path::new("out")), REPORT_SUFFIX),
+            PathBuf::from("out/agent.report.json")
+        );
+    }
+
+    #[test]
+    fn test_find_output_collision() {
+        let files = [PathBuf::from("in/agent.json"), PathBuf::from("in/other.json")];
+        assert_eq!(find_output_collision(&files, None), None);
+
+        let files = [PathBuf::from("in/agent.json"), PathBuf::from("in/agent.yaml")];
+        assert_eq!(
+            find_output_collision(&files, None),
+            Some("in/agent.json and in/agent.yaml would both be written to in/agent.nga.yaml".to_string())
+        );
+
+        let files = [PathBuf::from("a/agent.json"), PathBuf::from("b/agent.json")];
+        assert_eq!(find_output_collision(&files, None), None);
+        assert_eq!(
+            find_output_collision(&files, Some(Path::new("out"))),
+            Some("a/agent.json and b/agent.json would both be written to out/agent.nga.yaml".to_string())
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use crate::models::*;
 use crate::helpers::*;
+use crate::error::{path_field, path_index};
+use crate::rule_translator::{condition_variables, translate_rules};
+use crate::validator::Diagnostic;
 use crate::variable_processor::*;
 
 // ============================================================================
@@ -150,27 +154,41 @@ fn detect_agent_type(input: &AgentforceInput) -> String {
 }
 
 /// Detect input format and convert accordingly
-pub fn detect_and_convert(input: &AgentforceInput, rules: &Option<ConversionRules>) -> Result<NGAOutput, String> {
+/// Returns the output with the names changed to keep topic, action and parameter names
+/// usable and unique
+pub fn detect_and_convert(
+    input: &AgentforceInput,
+    rules: Option<&ConversionRules>,
+) -> (NGAOutput, Vec<Rename>) {
+    let mut renames = Vec::new();
+    
     // Check if it's a Salesforce Agentforce export (has plugins array)
     if let Some(plugins) = &input.plugins {
         if !plugins.is_empty() {
-            return convert_agentforce_format(input, rules);
+            let nga = convert_agentforce_format(input, rules, &mut renames);
+            return (nga, renames);
         }
     }
     
     // Check if it's already in NGA-like format (has topics array)
     if let Some(topics) = &input.topics {
         if !topics.is_empty() {
-            return convert_simple_format(input, rules);
+            let nga = convert_simple_format(input, rules, &mut renames);
+            return (nga, renames);
         }
     }
     
     // Fallback: try to convert as generic input
-    convert_generic_format(input, rules)
+    (convert_generic_format(input, rules), renames)
 }
 
 /// Convert Salesforce Agentforce JSON format to NGA
-pub fn convert_agentforce_format(input: &AgentforceInput, rules: &Option<ConversionRules>) -> Result<NGAOutput, String> {
+/// Renamed topics, actions and parameters are added to `renames`
+pub fn convert_agentforce_format(
+    input: &AgentforceInput,
+    rules: Option<&ConversionRules>,
+    renames: &mut Vec<Rename>,
+) -> NGAOutput {
     let mut nga = NGAOutput {
         system: SystemSection {
             instructions: build_system_instructions(input, rules),
@@ -207,12 +225,9 @@ pub fn convert_agentforce_format(input: &AgentforceInput, rules: &Option<Convers
             additional_locales: format_locales(input.secondary_locales.as_ref()),
             all_additional_locales: get_default_language_values().1,
         },
-        knowledge: KnowledgeSection {
-            rag_feature_config_id: String::new(),
-            citations_enabled: false,
-        },
-        topics: HashMap::new(),
-        connections: HashMap::new(),
+        knowledge: resolve_knowledge(input).knowledge,
+        topics: IndexMap::new(),
+        connections: IndexMap::new(),
     };
     
     // Connection section
@@ -222,7 +237,6 @@ pub fn convert_agentforce_format(input: &AgentforceInput, rules: &Option<Convers
         "messaging"
     };
     let adaptive_response = rules
-        .as_ref()
         .and_then(|r| {
             r.connection
                 .as_ref()
@@ -241,34 +255,136 @@ pub fn convert_agentforce_format(input: &AgentforceInput, rules: &Option<Convers
     
     // Topics section - Convert plugins to topics
     if let Some(plugins) = &input.plugins {
+        let topic_names = plugin_topic_names(plugins, renames);
+        
         // First, create the start_agent topic_selector
-        let topic_selector = create_topic_selector_from_plugins(plugins, rules)?;
+        let topic_selector = create_topic_selector_from_plugins(plugins, &topic_names, rules);
         nga.topics.insert("start_agent topic_selector".to_string(), topic_selector);
         
         // Then convert each plugin as a regular topic
-        for plugin in plugins {
-            if plugin.plugin_type.as_deref() != Some("TOPIC") {
+        for (index, (plugin, topic_name)) in plugins.iter().zip(&topic_names).enumerate() {
+            let Some(topic_name) = topic_name else {
                 continue;
-            }
+            };
             
-            let topic_name = sanitize_topic_name(
-                plugin.local_dev_name.as_deref().or(Some(plugin.name.as_str()))
-            );
+            let plugin_path = path_index("plugins", index);
             let topic_key = format!("topic {}", topic_name);
             
-            let topic = convert_plugin_to_topic(plugin, plugins, rules)?;
+            let topic = convert_plugin_to_topic(plugin, plugins, &nga.variables, rules, &plugin_path, renames);
             nga.topics.insert(topic_key, topic);
         }
     }
     
     // Add default topics if missing
-    ensure_default_topics(&mut nga, rules)?;
+    ensure_default_topics(&mut nga, rules);
+    describe_topic_transitions(&mut nga, rules);
+    
+    nga
+}
+
+/// A topic, action or parameter name before it is made unique
+struct NameCandidate {
+    /// JSON path of the name field in the input (e.g. `plugins[2].name`)
+    path: String,
+    /// Input value of the name field (empty when the field is absent)
+    original: String,
+    /// Sanitized name
+    name: String,
+    /// Whether nothing usable remained of `original` and `name` was
+    /// taken from the label or a placeholder
+    replaced: bool,
+}
+
+/// Sanitize a name with `sanitize`
+/// Falls back to the sanitized label, then to `placeholder`, when nothing usable
+/// remains; the flag tells whether the name was taken from one of those
+fn sanitize_name(
+    raw_name: &str,
+    label: Option<&str>,
+    sanitize: fn(Option<&str>) -> String,
+    placeholder: &str,
+) -> (String, bool) {
+    let name = sanitize(Some(raw_name));
+    if !name.is_empty() {
+        return (name, false);
+    }
+    let name = label
+        .map(|label| sanitize(Some(label)))
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| placeholder.to_string());
+    (name, true)
+}
+
+/// Build the name candidate for the name field at `path`
+fn name_candidate(
+    path: String,
+    raw_name: &str,
+    label: Option<&str>,
+    sanitize: fn(Option<&str>) -> String,
+    placeholder: &str,
+) -> NameCandidate {
+    let (name, replaced) = sanitize_name(raw_name, label, sanitize, placeholder);
+    NameCandidate { path, original: raw_name.to_string(), name, replaced }
+}
+
+/// Field holding a plugin's or function's name: `localDevName` when present, else `name`
+fn name_field(local_dev_name: Option<&str>) -> &'static str {
+    if local_dev_name.is_some() { "localDevName" } else { "name" }
+}
+
+/// Make names unique, recording a Rename for each name that was replaced or suffixed
+fn dedupe_names(named: Vec<NameCandidate>, renames: &mut Vec<Rename>) -> Vec<String> {
+    let names: Vec<String> = named.iter().map(|candidate| candidate.name.clone()).collect();
+    let unique = unique_names(&names);
+    for (candidate, name) in named.into_iter().zip(&unique) {
+        if candidate.replaced || candidate.name != *name {
+            renames.push(Rename { path: candidate.path, original: candidate.original, name: name.clone() });
+        }
+    }
+    unique
+}
+
+/// Unique topic names of the TOPIC plugins, index-aligned with `plugins` (None for other plugins)
+/// Names fall back to the label, then to `topic`, when nothing usable remains after sanitization
+pub fn plugin_topic_names(plugins: &[Plugin], renames: &mut Vec<Rename>) -> Vec<Option<String>> {
+    let named = plugins
+        .iter()
+        .enumerate()
+        .filter(|(_, plugin)| plugin.plugin_type.as_deref() == Some("TOPIC"))
+        .map(|(index, plugin)| {
+            let raw_name = plugin.local_dev_name.as_deref().unwrap_or(&plugin.name);
+            let path = path_field(&path_index("plugins", index), name_field(plugin.local_dev_name.as_deref()));
+            name_candidate(path, raw_name, plugin.label.as_deref(), sanitize_topic_name, "topic")
+        })
+        .collect();
     
-    Ok(nga)
+    let mut unique = dedupe_names(named, renames).into_iter();
+    plugins
+        .iter()
+        .map(|plugin| if plugin.plugin_type.as_deref() == Some("TOPIC") { unique.next() } else { None })
+        .collect()
+}
+
+/// Unique topic names of simple-format topics, index-aligned with `topics`
+/// Names fall back to the label, then to `topic`, when nothing usable remains after sanitization
+pub fn simple_topic_names(topics: &[TopicInput], renames: &mut Vec<Rename>) -> Vec<String> {
+    let named = topics
+        .iter()
+        .enumerate()
+        .map(|(index, topic)| {
+            let topic_path = path_index("topics", index);
+            match (topic.name.as_deref(), topic.id.as_deref()) {
+                (Some(name), _) => name_candidate(path_field(&topic_path, "name"), name, topic.label.as_deref(), sanitize_topic_name, "topic"),
+                (None, Some(id)) => name_candidate(path_field(&topic_path, "id"), id, topic.label.as_deref(), sanitize_topic_name, "topic"),
+                (None, None) => NameCandidate { path: path_field(&topic_path, "name"), original: String::new(), name: sanitize_topic_name(None), replaced: false },
+            }
+        })
+        .collect();
+    dedupe_names(named, renames)
 }
 
 /// Build comprehensive system instructions from input
-fn build_system_instructions(input: &AgentforceInput, rules: &Option<ConversionRules>) -> String {
+fn build_system_instructions(input: &AgentforceInput, rules: Option<&ConversionRules>) -> String {
     let mut parts = Vec::new();
     
     if let Some(role) = &input.planner_role {
@@ -323,9 +439,116 @@ fn extract_welcome_message(input: &AgentforceInput) -> String {
     format!("Hi, I'm {}. How can I help you today?", label)
 }
 
+/// Knowledge settings resolved from the planner's ADL configuration
+#[derive(Debug, Clone)]
+pub struct KnowledgeSelection {
+    /// The `knowledge` block to write
+    pub knowledge: KnowledgeSection,
+    /// RAG feature names of every grounding library the input configures
+    pub candidates: Vec<String>,
+}
+
+/// Resolve the knowledge block from `adlPlannerConfigurations` and the
+/// `ragFeatureConfigId`/`citationsEnabled` constants on function inputs
+///
+/// When several grounding libraries are configured, the first one a function
+/// is bound to wins, otherwise the first one listed.
+pub fn resolve_knowledge(input: &AgentforceInput) -> KnowledgeSelection {
+    let mut candidates: Vec<String> = Vec::new();
+    for config in input.adl_planner_configurations.iter().flatten() {
+        let name = config.rag_feature_name.as_ref()
+            .or(config.rag_feature_fully_qualified_name.as_ref())
+            .or(config.rag_feature_id.as_ref());
+        if let Some(name) = name {
+            if !name.is_empty() && !candidates.contains(name) {
+                candidates.push(name.clone());
+            }
+        }
+    }
+    
+    // (ragFeatureConfigId, citationsEnabled) constants of each grounded function
+    let mut bindings: Vec<(Option<String>, Option<bool>)> = Vec::new();
+    let functions = input.plugins.iter().flatten().flat_map(|p| p.functions.iter().flatten());
+    for func in functions {
+        if let Some(properties) = func.input_type.as_ref().and_then(|t| t.properties.as_ref()) {
+            let config_id = properties.get("ragFeatureConfigId")
+                .and_then(|p| p.const_value.as_ref())
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            let citations = properties.get("citationsEnabled")
+                .and_then(|p| p.const_value.as_ref())
+                .and_then(|v| v.as_bool());
+            if config_id.is_some() || citations.is_some() {
+                bindings.push((config_id, citations));
+            }
+        }
+    }
+    
+    let rag_feature_config_id = candidates.iter()
+        .find(|name| bindings.iter().any(|(id, _)| id.as_ref() == Some(*name)))
+        .or(candidates.first())
+        .cloned()
+        .or_else(|| bindings.iter().find_map(|(id, _)| id.clone()))
+        .unwrap_or_default();
+    
+    let citations_enabled = !rag_feature_config_id.is_empty()
+        && bindings.iter().any(|(id, citations)| {
+            *citations == Some(true) && id.as_ref().is_none_or(|id| *id == rag_feature_config_id)
+        });
+    
+    KnowledgeSelection {
+        knowledge: KnowledgeSection { rag_feature_config_id, citations_enabled },
+        candidates,
+    }
+}
+
+/// Free text of an agent definition that may hold `{!$Var}` style references
+/// (instructions, descriptions, scopes and messages)
+fn input_texts(input: &AgentforceInput) -> Vec<&str> {
+    fn property_texts<'a>(io: &'a Option<InputOutputType>, texts: &mut Vec<&'a str>) {
+        let properties = io.iter().flat_map(|t| t.properties.iter().flatten());
+        texts.extend(properties.filter_map(|(_, p)| p.description.as_deref()));
+    }
+
+    let mut texts: Vec<&str> = [&input.description, &input.planner_role, &input.planner_company, &input.welcome_message]
+        .into_iter()
+        .filter_map(|text| text.as_deref())
+        .collect();
+
+    for plugin in input.plugins.iter().flatten() {
+        texts.extend(plugin.description.as_deref());
+        texts.extend(plugin.scope.as_deref());
+        texts.extend(plugin.instruction_definitions.iter().flatten().filter_map(|i| i.description.as_deref()));
+        for func in plugin.functions.iter().flatten() {
+            texts.extend(func.description.as_deref());
+            property_texts(&func.input_type, &mut texts);
+            property_texts(&func.output_type, &mut texts);
+        }
+    }
+
+    for topic in input.topics.iter().flatten() {
+        for text in [&topic.description, &topic.scope, &topic.instructions, &topic.reasoning] {
+            texts.extend(text.as_deref());
+        }
+        for action in topic.actions.iter().flatten() {
+            texts.extend(action.description.as_deref());
+            let properties = action.inputs.iter().chain(action.outputs.iter()).flatten();
+            texts.extend(properties.filter_map(|(_, p)| p.description.as_deref()));
+        }
+    }
+
+    texts.extend(input.variables.iter().flatten().filter_map(|v| v.description.as_deref()));
+    texts
+}
+
+/// Check if the free text of an agent definition has variables to convert to @variables format
+pub fn has_dollar_variables(input: &AgentforceInput, rules: Option<&ConversionRules>) -> bool {
+    check_for_dollar_variables(&input_texts(input).join("\n"), rules)
+}
+
 /// Extract only variables that are actually used/referenced in the agent definition
-fn extract_variables(input: &AgentforceInput, rules: &Option<ConversionRules>) -> HashMap<String, Variable> {
-    let mut variables = HashMap::new();
+fn extract_variables(input: &AgentforceInput, rules: Option<&ConversionRules>) -> IndexMap<String, Variable> {
+    let mut variables = IndexMap::new();
     
     // First, collect all text content where variables might be referenced
     let mut all_text_content = String::new();
@@ -377,7 +600,20 @@ fn extract_variables(input: &AgentforceInput, rules: &Option<ConversionRules>) -
     
     // Find all variable references in the text content
     // Patterns: {!$VarName}, {$!VarName}, {$VarName}, {!VarName}, @variables.VarName
-    let referenced_vars = find_variable_references(&all_text_content);
+    let mut referenced_vars = find_variable_references(&all_text_content);
+
+    // Variables compared by rule expressions must be defined for the conditions
+    for plugin in input.plugins.iter().flatten() {
+        let rule_sets = std::iter::once(&plugin.rule_expressions)
+            .chain(plugin.functions.iter().flatten().map(|f| &f.rule_expressions));
+        for expressions in rule_sets {
+            for name in condition_variables(expressions.as_deref()) {
+                if !referenced_vars.contains(&name) {
+                    referenced_vars.push(name);
+                }
+            }
+        }
+    }
     
     // If no variables are referenced, return empty
     if referenced_vars.is_empty() {
@@ -550,7 +786,7 @@ fn find_variable_references(text: &str) -> Vec<String> {
 fn map_property_type(
     json_type: Option<&str>,
     prop: &Property,
-    rules: &Option<ConversionRules>,
+    rules: Option<&ConversionRules>,
 ) -> String {
     let json_type = json_type.unwrap_or("object");
     
@@ -566,7 +802,6 @@ fn map_property_type(
     
     // Get type mappings from rules
     let primitive_map: HashMap<&str, &str> = rules
-        .as_ref()
         .and_then(|r| r.type_mappings.as_ref())
         .and_then(|tm| tm.primitive.as_ref())
         .map(|pm| {
@@ -587,7 +822,6 @@ fn map_property_type(
         });
     
     let complex_map: HashMap<&str, &str> = rules
-        .as_ref()
         .and_then(|r| r.type_mappings.as_ref())
         .and_then(|tm| tm.complex.as_ref())
         .map(|cm| {
@@ -603,7 +837,6 @@ fn map_property_type(
         });
     
     let default_type = rules
-        .as_ref()
         .and_then(|r| r.type_mappings.as_ref())
         .and_then(|tm| tm.default_type.as_ref()).cloned()
         .unwrap_or_else(|| "object".to_string());
@@ -643,16 +876,35 @@ fn derive_complex_data_type_name(prop_type: &str) -> Option<String> {
 }
 
 /// Convert a plugin to an NGA topic
+/// `variables` are the agent's variables, which action parameters can be bound to
+/// `plugin_path` is the JSON path of the plugin (e.g. `plugins[3]`), used in errors and renames
+/// Renamed actions and parameters are added to `renames`
 pub fn convert_plugin_to_topic(
     plugin: &Plugin,
     _all_plugins: &[Plugin],
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
+    variables: &IndexMap<String, Variable>,
+    rules: Option<&ConversionRules>,
+    plugin_path: &str,
+    renames: &mut Vec<Rename>,
+) -> Topic {
     let instructions = build_topic_instructions(plugin, rules);
-    let actions = build_detailed_actions(plugin, rules)?;
+    let action_names = plugin_action_names(plugin, plugin_path, renames);
+    let actions = build_detailed_actions(plugin, &action_names, rules, plugin_path, renames);
     
     // Build reasoning action references from detailed actions
-    let reasoning_actions = build_reasoning_action_references(&actions);
+    let mut reasoning_actions = build_reasoning_action_references(&actions, variables);
+    
+    // Guard actions with the visibility rules of their functions
+    for (func, action_name) in plugin.functions.iter().flatten().zip(&action_names) {
+        if let Some(action) = reasoning_actions.get_mut(action_name) {
+            translate_rules(func.rule_expressions.as_deref(), func.workflow_config.as_ref(), rules).apply(action);
+        }
+    }
+    
+    // Escalating topics can hand the conversation over to a human agent
+    if let Some((name, escalation)) = plugin_escalation(plugin) {
+        reasoning_actions.insert(name, escalation);
+    }
     
     let fallback_name = plugin
         .label
@@ -666,32 +918,77 @@ pub fn convert_plugin_to_topic(
         &fallback_name,
     );
     
-    Ok(Topic {
+    Topic {
         label: plugin
             .label
             .clone()
             .unwrap_or_else(|| format_label(&plugin.name)),
         description: merged_description,
+        classification_description: build_classification_description(plugin),
         reasoning: ReasoningSection {
             instructions,
             actions: if reasoning_actions.is_empty() { None } else { Some(reasoning_actions) },
         },
         actions: Some(actions),
-    })
+    }
+}
+
+/// The `@utils.escalate` reasoning action a plugin with `canEscalate` gets, with its name
+/// None when the plugin cannot escalate or one of its functions already uses the name
+pub fn plugin_escalation(plugin: &Plugin) -> Option<(String, ReasoningAction)> {
+    if plugin.can_escalate != Some(true) {
+        return None;
+    }
+    let name_taken = plugin
+        .functions
+        .iter()
+        .flatten()
+        .any(|func| function_action_name(func) == ESCALATION_ACTION_NAME);
+    if name_taken {
+        return None;
+    }
+    
+    Some((
+        ESCALATION_ACTION_NAME.to_string(),
+        ReasoningAction {
+            target: ESCALATE_TARGET.to_string(),
+            description: Some(ESCALATION_ACTION_DESCRIPTION.to_string()),
+            with_params: None,
+            set_clauses: None,
+            condition: None,
+            comments: None,
+        },
+    ))
 }
 
 /// Build reasoning action references from detailed actions
+/// Inputs get `with` bindings and outputs feeding a non-linked variable get `set` clauses
 fn build_reasoning_action_references(
-    actions: &HashMap<String, Action>
-) -> HashMap<String, ReasoningAction> {
-    let mut reasoning_actions = HashMap::new();
+    actions: &IndexMap<String, Action>,
+    variables: &IndexMap<String, Variable>,
+) -> IndexMap<String, ReasoningAction> {
+    let mut reasoning_actions = IndexMap::new();
     for (action_name, action) in actions {
-        let with_params = action.inputs.as_ref()
-            .map(|inputs| {
-                let mut params: Vec<String> = inputs.keys().cloned().collect();
-                params.sort();
-                params
-            });
+        let with_params = action.inputs.as_ref().map(|inputs| {
+            inputs
+                .iter()
+                .filter_map(|(name, input)| {
+                    bind_input(name, input, variables).map(|binding| WithParam { name: name.clone(), binding })
+                })
+                .collect::<Vec<WithParam>>()
+        });
+        let set_clauses = action.outputs.as_ref()
+            .map(|outputs| {
+                outputs
+                    .keys()
+                    .filter_map(|output| {
+                        find_variable(output, variables)
+                            .filter(|(_, variable)| !variable.var_type.starts_with("linked "))
+                            .map(|(name, _)| SetClause { variable: name.clone(), output: output.clone() })
+                    })
+                    .collect::<Vec<SetClause>>()
+            })
+            .filter(|clauses| !clauses.is_empty());
         
         reasoning_actions.insert(
             action_name.clone(),
@@ -699,14 +996,68 @@ fn build_reasoning_action_references(
                 target: format!("@actions.{}", action_name),
                 description: None,
                 with_params,
+                set_clauses,
+                condition: None,
+                comments: None,
             },
         );
     }
     reasoning_actions
 }
 
+/// Choose the `with` binding for an action input
+///
+/// A const/default value is bound as a literal; otherwise an input named like
+/// one of the agent's variables (exactly, else ignoring case) is bound to it.
+/// Remaining user inputs are slot-filled by the planner; remaining non-user
+/// inputs get no `with` clause, since nobody could supply them.
+fn bind_input(
+    name: &str,
+    input: &ActionInputDef,
+    variables: &IndexMap<String, Variable>,
+) -> Option<WithBinding> {
+    let literal = input.const_value.as_ref()
+        .filter(|value| value.is_string() || value.is_boolean() || value.is_number());
+    if let Some(value) = literal {
+        return Some(WithBinding::Literal(value.clone()));
+    }
+
+    if let Some((var_name, _)) = find_variable(name, variables) {
+        return Some(WithBinding::Variable(var_name.clone()));
+    }
+
+    input.is_user_input.then_some(WithBinding::SlotFill)
+}
+
+/// Find the variable an action input or output feeds: same name, else same name ignoring case
+fn find_variable<'a>(
+    name: &str,
+    variables: &'a IndexMap<String, Variable>,
+) -> Option<(&'a String, &'a Variable)> {
+    variables.get_key_value(name)
+        .or_else(|| variables.iter().find(|(var_name, _)| var_name.eq_ignore_ascii_case(name)))
+}
+
+/// Build a topic's classification description from the plugin's example utterances
+/// Returns None when the plugin has no utterances
+pub fn build_classification_description(plugin: &Plugin) -> Option<String> {
+    let mut examples: Vec<&str> = Vec::new();
+    for utterance in plugin.utterance_definitions.iter().flatten().filter_map(|u| u.utterance.as_deref()) {
+        let utterance = utterance.trim();
+        if !utterance.is_empty() && !examples.contains(&utterance) {
+            examples.push(utterance);
+        }
+    }
+    if examples.is_empty() {
+        return None;
+    }
+    
+    let quoted: Vec<String> = examples.iter().map(|e| format!("\"{}\"", e)).collect();
+    Some(format!("{} {}", CLASSIFICATION_EXAMPLES_PREFIX, quoted.join("; ")))
+}
+
 /// Build topic instructions from instructionDefinitions
-fn build_topic_instructions(plugin: &Plugin, rules: &Option<ConversionRules>) -> String {
+fn build_topic_instructions(plugin: &Plugin, rules: Option<&ConversionRules>) -> String {
     let mut parts = Vec::new();
     
     // Add scope as initial context
@@ -757,18 +1108,46 @@ fn build_topic_instructions(plugin: &Plugin, rules: &Option<ConversionRules>) ->
     }
 }
 
-/// Build detailed actions from functions
+/// Resolve the sanitized action name for a function
+/// Falls back to the label, then to `action`, when nothing usable remains after sanitization
+pub fn function_action_name(func: &Function) -> String {
+    let raw_name = func.local_dev_name.as_deref().unwrap_or(&func.name);
+    sanitize_name(raw_name, func.label.as_deref(), sanitize_action_name, "action").0
+}
+
+/// Unique action names of a plugin's functions, index-aligned with its functions
+/// Names fall back to the label, then to `action`, when nothing usable remains after sanitization
+pub fn plugin_action_names(plugin: &Plugin, plugin_path: &str, renames: &mut Vec<Rename>) -> Vec<String> {
+    let named = plugin
+        .functions
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, func)| {
+            let raw_name = func.local_dev_name.as_deref().unwrap_or(&func.name);
+            let func_path = path_index(&path_field(plugin_path, "functions"), index);
+            let path = path_field(&func_path, name_field(func.local_dev_name.as_deref()));
+            name_candidate(path, raw_name, func.label.as_deref(), sanitize_action_name, "action")
+        })
+        .collect();
+    dedupe_names(named, renames)
+}
+
+/// Build detailed actions from functions, named by `action_names` (index-aligned with the functions)
+/// Renamed parameters are added to `renames`
 fn build_detailed_actions(
     plugin: &Plugin,
-    rules: &Option<ConversionRules>,
-) -> Result<HashMap<String, Action>, String> {
-    let mut actions = HashMap::new();
+    action_names: &[String],
+    rules: Option<&ConversionRules>,
+    plugin_path: &str,
+    renames: &mut Vec<Rename>,
+) -> IndexMap<String, Action> {
+    let mut actions = IndexMap::new();
     
     if let Some(functions) = &plugin.functions {
-        for func in functions {
-            let action_name = sanitize_action_name(
-                func.local_dev_name.as_deref().or(Some(func.name.as_str()))
-            );
+        for (index, (func, action_name)) in functions.iter().zip(action_names).enumerate() {
+            let func_path = path_index(&path_field(plugin_path, "functions"), index);
+            let action_name = action_name.clone();
             
             let fallback_desc = func
                 .description
@@ -790,59 +1169,192 @@ fn build_detailed_actions(
             
             // Add inputs if present
             if let Some(input_type) = &func.input_type {
-                action.inputs = Some(build_detailed_inputs(input_type, rules));
+                let input_path = path_field(&func_path, "inputType");
+                let input_names = parameter_names(input_type, "Input:", "input", &input_path, renames);
+                action.inputs = Some(build_detailed_inputs(input_type, &input_names, rules));
             }
             
             // Add outputs if present
             if let Some(output_type) = &func.output_type {
-                action.outputs = Some(build_detailed_outputs(output_type, rules));
+                let output_path = path_field(&func_path, "outputType");
+                let output_names = parameter_names(output_type, "Output:", "output", &output_path, renames);
+                action.outputs = Some(build_detailed_outputs(output_type, &output_names, rules));
             }
             
             actions.insert(action_name, action);
         }
     }
     
-    Ok(actions)
+    actions
+}
+
+/// Unique parameter names of an input/output type once its `Input:`/`Output:` prefix
+/// is removed, index-aligned with its properties
+/// Names that are empty without the prefix become `placeholder`
+fn parameter_names(
+    io_type: &InputOutputType,
+    prefix: &str,
+    placeholder: &str,
+    io_path: &str,
+    renames: &mut Vec<Rename>,
+) -> Vec<String> {
+    let named = io_type
+        .properties
+        .iter()
+        .flatten()
+        .map(|(name, _)| {
+            let path = path_field(&path_field(io_path, "properties"), name);
+            let clean_name = name.replace(prefix, "");
+            if clean_name.trim().is_empty() {
+                NameCandidate { path, original: name.clone(), name: placeholder.to_string(), replaced: true }
+            } else {
+                NameCandidate { path, original: name.clone(), name: clean_name, replaced: false }
+            }
+        })
+        .collect();
+    dedupe_names(named, renames)
 }
 
 /// Build detailed action target
-fn build_detailed_action_target(func: &Function, _rules: &Option<ConversionRules>) -> String {
+fn build_detailed_action_target(func: &Function, rules: Option<&ConversionRules>) -> String {
     let target_type = func
         .invocation_target_type
         .as_deref()
-        .unwrap_or("action");
-            let target_name = func
-                .invocation_target_name
-                .as_deref()
-                .or(func.invocation_target_id.as_deref())
-                .or(Some(func.name.as_str()))
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+        .unwrap_or(DEFAULT_TARGET_TYPE);
+    let target_name = func
+        .invocation_target_name
+        .as_deref()
+        .or(func.invocation_target_id.as_deref())
+        .unwrap_or(&func.name);
+    
+    build_action_target(target_type, target_name, rules)
+}
+
+/// Build an action target from an invocation type and target name
+/// The type is mapped through `target_format.mappings` (unmapped types are kept as is),
+/// the name goes through the type's `target_format.name_transforms` entry and both
+/// fill `target_format.syntax`
+pub fn build_action_target(target_type: &str, target_name: &str, rules: Option<&ConversionRules>) -> String {
+    let mapped_type = get_target_mapping(target_type, rules).unwrap_or_else(|| target_type.to_string());
+    let transform = get_target_name_transform(target_type, rules);
+    let name = transform_target_name(target_name, transform.as_deref().unwrap_or(TARGET_NAME_TRANSFORMS[0]));
+    
+    get_target_syntax(rules)
+        .replace("{invocation_type}", &mapped_type)
+        .replace("{target_name}", &name)
+}
+
+/// Get the target syntax from the rules
+/// A syntax without a `{target_name}` placeholder falls back to the default
+fn get_target_syntax(rules: Option<&ConversionRules>) -> String {
+    if let Some(rules) = rules {
+        if let Some(target_format) = &rules.target_format {
+            if let Some(syntax) = &target_format.syntax {
+                if syntax.contains("{target_name}") {
+                    return syntax.clone();
+                }
+            }
+        }
+    }
+    DEFAULT_TARGET_SYNTAX.to_string()
+}
+
+/// Get the scheme an invocation type maps to
+/// Without rule mappings the default types map to themselves
+fn get_target_mapping(target_type: &str, rules: Option<&ConversionRules>) -> Option<String> {
+    if let Some(rules) = rules {
+        if let Some(target_format) = &rules.target_format {
+            if let Some(mappings) = &target_format.mappings {
+                return mappings.get(target_type).cloned();
+            }
+        }
+    }
+    DEFAULT_TARGET_TYPES.contains(&target_type).then(|| target_type.to_string())
+}
+
+/// Get the name transform configured for an invocation type
+fn get_target_name_transform(target_type: &str, rules: Option<&ConversionRules>) -> Option<String> {
+    if let Some(rules) = rules {
+        if let Some(target_format) = &rules.target_format {
+            if let Some(transforms) = &target_format.name_transforms {
+                return transforms.get(target_type).cloned();
+            }
+        }
+    }
+    None
+}
+
+/// Apply a name transform; unknown transforms keep the name as is
+fn transform_target_name(name: &str, transform: &str) -> String {
+    match transform {
+        "lowercase" => name.to_lowercase(),
+        "snake_case" => sanitize_topic_name(Some(name)),
+        "strip_namespace" => name.rsplit_once("__").map_or(name, |(_, rest)| rest).to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Warn about invocation types without a target mapping and unknown name transforms
+/// The targets still use the type as is; paths point into the input
+pub fn check_target_mappings(input: &AgentforceInput, rules: Option<&ConversionRules>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut check = |target_type: &str, path: String| {
+        if get_target_mapping(target_type, rules).is_none() {
+            diagnostics.push(Diagnostic::warning(
+                path.as_str(),
+                format!("Invocation type '{}' has no mapping in target_format.mappings; its target uses it as is", target_type),
+            ));
+        }
+        if let Some(transform) = get_target_name_transform(target_type, rules) {
+            if !TARGET_NAME_TRANSFORMS.contains(&transform.as_str()) {
+                diagnostics.push(Diagnostic::warning(
+                    path.as_str(),
+                    format!("Unknown name transform '{}' for invocation type '{}'; the name is kept as is", transform, target_type),
+                ));
+            }
+        }
+    };
     
-    // Map invocation types
-    let type_map: HashMap<&str, &str> = [
-        ("flow", "flow"),
-        ("apex", "apex"),
-        ("standardInvocableAction", "standardInvocableAction"),
-        ("generatePromptResponse", "generatePromptResponse"),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-    
-    let mapped_type = type_map.get(target_type).copied().unwrap_or(target_type);
-    format!("{}://{}", mapped_type, target_name)
+    for (plugin_index, plugin) in input.plugins.iter().flatten().enumerate() {
+        let functions_path = path_field(&path_index("plugins", plugin_index), "functions");
+        for (index, func) in plugin.functions.iter().flatten().enumerate() {
+            let target_type = func.invocation_target_type.as_deref().unwrap_or(DEFAULT_TARGET_TYPE);
+            check(target_type, path_field(&path_index(&functions_path, index), "invocationTargetType"));
+        }
+    }
+    for (topic_index, topic) in input.topics.iter().flatten().enumerate() {
+        let actions_path = path_field(&path_index("topics", topic_index), "actions");
+        for (index, action) in topic.actions.iter().flatten().enumerate() {
+            if let Some(target_type) = simple_action_target_type(action) {
+                check(target_type, path_field(&path_index(&actions_path, index), "type"));
+            }
+        }
+    }
+    diagnostics
 }
 
-/// Build detailed inputs
+/// The invocation type of a simple-format action whose target the converter builds
+/// None when the action is a transition or escalation, or already has a full target
+fn simple_action_target_type(action: &ActionInput) -> Option<&str> {
+    if action.target.is_some() || matches!(action.action_type.as_deref(), Some("transition") | Some("escalate")) {
+        return None;
+    }
+    if action.invocation_target.as_deref().is_some_and(|t| t.contains("://")) {
+        return None;
+    }
+    Some(action.action_type.as_deref().unwrap_or(DEFAULT_TARGET_TYPE))
+}
+
+/// Build detailed inputs, named by `input_names` (index-aligned with the properties)
 fn build_detailed_inputs(
     input_type: &InputOutputType,
-    rules: &Option<ConversionRules>,
-) -> HashMap<String, ActionInputDef> {
-    let mut inputs = HashMap::new();
+    input_names: &[String],
+    rules: Option<&ConversionRules>,
+) -> IndexMap<String, ActionInputDef> {
+    let mut inputs = IndexMap::new();
     
     if let Some(properties) = &input_type.properties {
-        for (name, prop) in properties {
+        for ((name, prop), clean_name) in properties.iter().zip(input_names.iter().cloned()) {
             // Use is_user_input from property - skip inputs where is_user_input is explicitly false
             let is_user_input = prop.is_user_input.unwrap_or(true);
             
@@ -851,7 +1363,6 @@ fn build_detailed_inputs(
                 continue;
             }
             
-            let clean_name = name.replace("Input:", "");
             let prop_type = map_property_type(prop.prop_type.as_deref(), prop, rules);
             
             // Determine is_required from the required array
@@ -889,16 +1400,16 @@ fn build_detailed_inputs(
     inputs
 }
 
-/// Build detailed outputs
+/// Build detailed outputs, named by `output_names` (index-aligned with the properties)
 fn build_detailed_outputs(
     output_type: &InputOutputType,
-    rules: &Option<ConversionRules>,
-) -> HashMap<String, ActionOutputDef> {
-    let mut outputs = HashMap::new();
+    output_names: &[String],
+    rules: Option<&ConversionRules>,
+) -> IndexMap<String, ActionOutputDef> {
+    let mut outputs = IndexMap::new();
     
     if let Some(properties) = &output_type.properties {
-        for (name, prop) in properties {
-            let clean_name = name.replace("Output:", "");
+        for (prop, clean_name) in properties.values().zip(output_names.iter().cloned()) {
             let prop_type = map_property_type(prop.prop_type.as_deref(), prop, rules);
             
             // Use values from property if available, otherwise use defaults
@@ -947,10 +1458,12 @@ fn build_detailed_outputs(
 }
 
 /// Convert simple format (with topics array) to NGA
+/// Renamed topics and actions are added to `renames`
 pub fn convert_simple_format(
     input: &AgentforceInput,
-    rules: &Option<ConversionRules>,
-) -> Result<NGAOutput, String> {
+    rules: Option<&ConversionRules>,
+    renames: &mut Vec<Rename>,
+) -> NGAOutput {
     let defaults = get_default_system_values();
     let lang_defaults = get_default_language_values();
     
@@ -985,7 +1498,7 @@ pub fn convert_simple_format(
                 .description.clone()
                 .unwrap_or_else(|| "Service Agent".to_string()),
         },
-        variables: HashMap::new(),
+        variables: IndexMap::new(),
         language: LanguageSection {
             default_locale: input
                 .locale.clone()
@@ -997,8 +1510,8 @@ pub fn convert_simple_format(
             rag_feature_config_id: String::new(),
             citations_enabled: false,
         },
-        topics: HashMap::new(),
-        connections: HashMap::new(),
+        topics: IndexMap::new(),
+        connections: IndexMap::new(),
     };
     
     // Variables section
@@ -1032,7 +1545,6 @@ pub fn convert_simple_format(
     
     // Connection section
     let adaptive_response = rules
-        .as_ref()
         .and_then(|r| {
             r.connection
                 .as_ref()
@@ -1051,13 +1563,15 @@ pub fn convert_simple_format(
     
     // Topics section
     if let Some(topics) = &input.topics {
+        let topic_names = simple_topic_names(topics, renames);
+        
         // Create topic selector
-        let topic_selector = create_topic_selector_from_simple_topics(topics, rules)?;
+        let topic_selector = create_topic_selector_from_simple_topics(&topic_names, rules);
         nga.topics.insert("start_agent topic_selector".to_string(), topic_selector);
         
         // Convert each topic
-        for topic in topics {
-            let topic_name = sanitize_topic_name(topic.name.as_deref().or(topic.id.as_deref()));
+        for (index, (topic, topic_name)) in topics.iter().zip(topic_names).enumerate() {
+            let topic_path = path_index("topics", index);
             let topic_key = format!("topic {}", topic_name);
             
             let nga_topic = Topic {
@@ -1069,6 +1583,7 @@ pub fn convert_simple_format(
                     topic.scope.as_deref(),
                     &topic_name,
                 ),
+                classification_description: None,
                 reasoning: ReasoningSection {
                     instructions: topic
                         .instructions
@@ -1077,41 +1592,71 @@ pub fn convert_simple_format(
                         .unwrap_or_else(|| "Handle user requests appropriately.".to_string()),
                     actions: None,
                 },
-                actions: convert_simple_actions_detailed(topic.actions.as_ref(), rules).ok(),
+                actions: Some(convert_simple_actions_detailed(topic.actions.as_ref(), &topic_path, rules, renames)),
             };
             
             nga.topics.insert(topic_key, nga_topic);
         }
     }
     
-    ensure_default_topics(&mut nga, rules)?;
-    Ok(nga)
+    ensure_default_topics(&mut nga, rules);
+    describe_topic_transitions(&mut nga, rules);
+    nga
+}
+
+/// Check if a simple-format action is a transition or escalation, which become no action
+fn is_simple_transition_action(action: &ActionInput) -> bool {
+    action.target.is_some()
+        || matches!(action.action_type.as_deref(), Some("transition") | Some("escalate"))
+}
+
+/// Unique names of a simple-format topic's actions, index-aligned with them
+/// (None for transitions and escalations, which become no action)
+/// Blank names fall back to the sanitized label, then to `action`
+pub fn simple_action_names(actions: &[ActionInput], topic_path: &str, renames: &mut Vec<Rename>) -> Vec<Option<String>> {
+    let named = actions
+        .iter()
+        .enumerate()
+        .filter(|(_, action)| !is_simple_transition_action(action))
+        .map(|(index, action)| {
+            let action_path = path_index(&path_field(topic_path, "actions"), index);
+            let field = if action.name.is_none() && action.id.is_some() { "id" } else { "name" };
+            let path = path_field(&action_path, field);
+            match action.name.as_deref().or(action.id.as_deref()) {
+                Some(name) if name.trim().is_empty() => {
+                    let (label_name, _) = sanitize_name("", action.label.as_deref(), sanitize_action_name, "action");
+                    NameCandidate { path, original: name.to_string(), name: label_name, replaced: true }
+                }
+                Some(name) => NameCandidate { path, original: name.to_string(), name: name.to_string(), replaced: false },
+                None => NameCandidate { path, original: String::new(), name: "action".to_string(), replaced: false },
+            }
+        })
+        .collect();
+    
+    let mut unique = dedupe_names(named, renames).into_iter();
+    actions
+        .iter()
+        .map(|action| if is_simple_transition_action(action) { None } else { unique.next() })
+        .collect()
 }
 
 /// Convert simple actions to detailed format
+/// Renamed actions are added to `renames`
 fn convert_simple_actions_detailed(
     actions: Option<&Vec<ActionInput>>,
-    _rules: &Option<ConversionRules>,
-) -> Result<HashMap<String, Action>, String> {
-    let mut result = HashMap::new();
+    topic_path: &str,
+    rules: Option<&ConversionRules>,
+    renames: &mut Vec<Rename>,
+) -> IndexMap<String, Action> {
+    let mut result = IndexMap::new();
     
     if let Some(actions) = actions {
-        for action in actions {
-            let action_name = action
-                .name
-                .as_ref()
-                .or(action.id.as_ref()).cloned()
-                .unwrap_or_else(|| "action".to_string());
-            
-            // Skip transition-type actions
-            if action.target.is_some() || action.action_type.as_deref() == Some("transition") {
+        let action_names = simple_action_names(actions, topic_path, renames);
+        for (action, action_name) in actions.iter().zip(action_names) {
+            // Transition and escalation actions are skipped
+            let Some(action_name) = action_name else {
                 continue;
-            }
-            
-            // Skip escalation actions
-            if action.action_type.as_deref() == Some("escalate") {
-                continue;
-            }
+            };
             
             let mut nga_action = Action {
                 description: action
@@ -1122,18 +1667,25 @@ fn convert_simple_actions_detailed(
                 include_in_progress_indicator: action.include_in_progress_indicator.unwrap_or(false),
                 progress_indicator_message: action.progress_indicator_message.clone(),
                 source: action.source.clone(),
-                target: action
-                    .invocation_target
-                    .as_ref()
-                    .or(action.target_name.as_ref()).cloned()
-                    .unwrap_or_else(|| format!("action://{}", action_name)),
+                // Full targets (`flow://Get_Order`) are kept; names are built like function targets
+                target: match simple_action_target_type(action) {
+                    Some(target_type) => {
+                        let target_name = action
+                            .invocation_target
+                            .as_deref()
+                            .or(action.target_name.as_deref())
+                            .unwrap_or(&action_name);
+                        build_action_target(target_type, target_name, rules)
+                    }
+                    None => action.invocation_target.clone().unwrap_or_default(),
+                },
                 inputs: None,
                 outputs: None,
             };
             
             // Add inputs if present
             if let Some(inputs) = &action.inputs {
-                let mut nga_inputs = HashMap::new();
+                let mut nga_inputs = IndexMap::new();
                 for (input_name, input_def) in inputs {
                     let input_type = input_def.prop_type.as_deref().unwrap_or("string").to_string();
                     
@@ -1163,7 +1715,7 @@ fn convert_simple_actions_detailed(
             
             // Add outputs if present
             if let Some(outputs) = &action.outputs {
-                let mut nga_outputs = HashMap::new();
+                let mut nga_outputs = IndexMap::new();
                 for (output_name, output_def) in outputs {
                     let output_type = output_def.prop_type.as_deref().unwrap_or("string").to_string();
                     
@@ -1194,14 +1746,14 @@ fn convert_simple_actions_detailed(
         }
     }
     
-    Ok(result)
+    result
 }
 
 /// Convert generic/unknown format to NGA
 pub fn convert_generic_format(
     input: &AgentforceInput,
-    rules: &Option<ConversionRules>,
-) -> Result<NGAOutput, String> {
+    rules: Option<&ConversionRules>,
+) -> NGAOutput {
     let defaults = get_default_system_values();
     let lang_defaults = get_default_language_values();
     
@@ -1230,7 +1782,7 @@ pub fn convert_generic_format(
                 .description.clone()
                 .unwrap_or_else(|| "Service Agent".to_string()),
         },
-        variables: HashMap::new(),
+        variables: IndexMap::new(),
         language: LanguageSection {
             default_locale: input
                 .locale.clone()
@@ -1242,13 +1794,12 @@ pub fn convert_generic_format(
             rag_feature_config_id: String::new(),
             citations_enabled: false,
         },
-        topics: HashMap::new(),
-        connections: HashMap::new(),
+        topics: IndexMap::new(),
+        connections: IndexMap::new(),
     };
     
     // Connection section
     let adaptive_response = rules
-        .as_ref()
         .and_then(|r| {
             r.connection
                 .as_ref()
@@ -1268,51 +1819,46 @@ pub fn convert_generic_format(
     // Create default topics
     nga.topics.insert(
         "start_agent topic_selector".to_string(),
-        create_default_topic_selector(rules)?,
+        create_default_topic_selector(rules),
     );
     nga.topics.insert(
         "topic escalation".to_string(),
-        create_default_escalation_topic(rules)?,
+        create_default_escalation_topic(rules),
     );
     nga.topics.insert(
         "topic off_topic".to_string(),
-        create_default_off_topic(rules)?,
+        create_default_off_topic(rules),
     );
     nga.topics.insert(
         "topic ambiguous_question".to_string(),
-        create_default_ambiguous_topic(rules)?,
+        create_default_ambiguous_topic(rules),
     );
     
-    Ok(nga)
+    nga
 }
 
 /// Create topic selector from plugins
 fn create_topic_selector_from_plugins(
     plugins: &[Plugin],
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
-    let mut actions = HashMap::new();
+    topic_names: &[Option<String>],
+    rules: Option<&ConversionRules>,
+) -> Topic {
+    let mut actions = IndexMap::new();
     let template = get_topic_selector_template(rules);
     
     // Add transitions to all topics from plugins
-    for plugin in plugins {
-        if plugin.plugin_type.as_deref() != Some("TOPIC") {
+    for (plugin, topic_name) in plugins.iter().zip(topic_names) {
+        let Some(topic_name) = topic_name else {
             continue;
-        }
+        };
         
-        let topic_name = sanitize_topic_name(
-            plugin.local_dev_name.as_deref().or(Some(plugin.name.as_str()))
-        );
         let action_name = format!("go_to_{}", topic_name);
         
-        actions.insert(
-            action_name,
-            ReasoningAction {
-                target: format!("@utils.transition to @topic.{}", topic_name),
-                description: None,
-                with_params: None,
-            },
-        );
+        let mut transition = build_topic_transition(topic_name);
+        // The topic is only reachable when its visibility rules hold
+        translate_rules(plugin.rule_expressions.as_deref(), plugin.workflow_config.as_ref(), rules)
+            .apply(&mut transition);
+        actions.insert(action_name, transition);
     }
     
     // Add default topic transitions
@@ -1321,38 +1867,31 @@ fn create_topic_selector_from_plugins(
         actions.entry(key).or_insert(value);
     }
     
-    Ok(Topic {
+    Topic {
         label: template.0,
         description: template.1,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions: template.2,
             actions: Some(actions),
         },
         actions: None,
-    })
+    }
 }
 
 /// Create topic selector from simple topics
 fn create_topic_selector_from_simple_topics(
-    topics: &[TopicInput],
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
-    let mut actions = HashMap::new();
+    topic_names: &[String],
+    rules: Option<&ConversionRules>,
+) -> Topic {
+    let mut actions = IndexMap::new();
     let template = get_topic_selector_template(rules);
     
     // Add transitions to all topics
-    for topic in topics {
-        let topic_name = sanitize_topic_name(topic.name.as_deref().or(topic.id.as_deref()));
+    for topic_name in topic_names {
         let action_name = format!("go_to_{}", topic_name);
         
-        actions.insert(
-            action_name,
-            ReasoningAction {
-                target: format!("@utils.transition to @topic.{}", topic_name),
-                description: None,
-                with_params: None,
-            },
-        );
+        actions.insert(action_name, build_topic_transition(topic_name));
     }
     
     // Add default topic transitions
@@ -1361,19 +1900,20 @@ fn create_topic_selector_from_simple_topics(
         actions.entry(key).or_insert(value);
     }
     
-    Ok(Topic {
+    Topic {
         label: template.0,
         description: template.1,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions: template.2,
             actions: Some(actions),
         },
         actions: None,
-    })
+    }
 }
 
 /// Get topic selector template from rules
-fn get_topic_selector_template(rules: &Option<ConversionRules>) -> (String, String, String) {
+fn get_topic_selector_template(rules: Option<&ConversionRules>) -> (String, String, String) {
     if let Some(rules) = rules {
         if let Some(templates) = &rules.templates {
             if let Some(topic_selector) = &templates.topic_selector {
@@ -1405,11 +1945,71 @@ fn get_topic_selector_template(rules: &Option<ConversionRules>) -> (String, Stri
     )
 }
 
+/// Build the topic selector's `go_to_<topic>` transition
+/// `describe_topic_transitions` adds its description once every topic exists
+fn build_topic_transition(topic_name: &str) -> ReasoningAction {
+    ReasoningAction {
+        target: format!("{}{}", TRANSITION_PREFIX, topic_name),
+        description: None,
+        with_params: None,
+        set_clauses: None,
+        condition: None,
+        comments: None,
+    }
+}
+
+/// Describe the topic selector's transitions that have no description yet
+/// The description fills `templates.topic_selector.transition_description` with the
+/// target topic's name, label, description and classification description
+fn describe_topic_transitions(nga: &mut NGAOutput, rules: Option<&ConversionRules>) {
+    let template = get_transition_description_template(rules);
+    let descriptions: HashMap<String, String> = nga
+        .topics
+        .iter()
+        .filter_map(|(key, topic)| {
+            let name = key.strip_prefix("topic ")?;
+            let description = template
+                .replace("{topic}", name)
+                .replace("{label}", &topic.label)
+                .replace("{classification}", topic.classification_description.as_deref().unwrap_or_default())
+                .replace("{description}", topic.description.trim());
+            let description = convert_variables_in_text(Some(description.trim()), rules);
+            (!description.is_empty()).then(|| (name.to_string(), description))
+        })
+        .collect();
+    
+    for (key, topic) in nga.topics.iter_mut() {
+        if !key.starts_with("start_agent ") {
+            continue;
+        }
+        for action in topic.reasoning.actions.iter_mut().flat_map(|actions| actions.values_mut()) {
+            let target = action.target.strip_prefix(TRANSITION_PREFIX).and_then(|name| descriptions.get(name));
+            if let (None, Some(description)) = (&action.description, target) {
+                action.description = Some(description.clone());
+            }
+        }
+    }
+}
+
+/// Get the transition description template from the rules
+fn get_transition_description_template(rules: Option<&ConversionRules>) -> String {
+    if let Some(rules) = rules {
+        if let Some(templates) = &rules.templates {
+            if let Some(topic_selector) = &templates.topic_selector {
+                if let Some(template) = &topic_selector.transition_description {
+                    return template.clone();
+                }
+            }
+        }
+    }
+    DEFAULT_TRANSITION_DESCRIPTION.to_string()
+}
+
 /// Get default topic transitions
 fn get_default_topic_transitions(
-    rules: &Option<ConversionRules>,
-) -> HashMap<String, ReasoningAction> {
-    let mut defaults = HashMap::new();
+    rules: Option<&ConversionRules>,
+) -> IndexMap<String, ReasoningAction> {
+    let mut defaults = IndexMap::new();
     
     // Get transitions from template if available
     if let Some(rules) = rules {
@@ -1436,6 +2036,9 @@ fn get_default_topic_transitions(
                                     target,
                                     description: None,
                                     with_params: None,
+                                    set_clauses: None,
+                                    condition: None,
+                                    comments: None,
                                 },
                             );
                         }
@@ -1453,6 +2056,9 @@ fn get_default_topic_transitions(
                 target: "@utils.transition to @topic.escalation".to_string(),
                 description: None,
                 with_params: None,
+                set_clauses: None,
+                condition: None,
+                comments: None,
             },
         );
     }
@@ -1463,6 +2069,9 @@ fn get_default_topic_transitions(
                 target: "@utils.transition to @topic.off_topic".to_string(),
                 description: None,
                 with_params: None,
+                set_clauses: None,
+                condition: None,
+                comments: None,
             },
         );
     }
@@ -1473,6 +2082,9 @@ fn get_default_topic_transitions(
                 target: "@utils.transition to @topic.ambiguous_question".to_string(),
                 description: None,
                 with_params: None,
+                set_clauses: None,
+                condition: None,
+                comments: None,
             },
         );
     }
@@ -1482,46 +2094,46 @@ fn get_default_topic_transitions(
 
 /// Create default topic selector
 fn create_default_topic_selector(
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
+    rules: Option<&ConversionRules>,
+) -> Topic {
     let template = get_topic_selector_template(rules);
     let default_transitions = get_default_topic_transitions(rules);
     
-    Ok(Topic {
+    Topic {
         label: template.0,
         description: template.1,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions: template.2,
             actions: Some(default_transitions),
         },
         actions: None,
-    })
+    }
 }
 
 /// Ensure default topics exist
 fn ensure_default_topics(
     nga: &mut NGAOutput,
-    rules: &Option<ConversionRules>,
-) -> Result<(), String> {
+    rules: Option<&ConversionRules>,
+) {
     if !has_topic_by_name(nga, "escalation") {
         nga.topics.insert(
             "topic escalation".to_string(),
-            create_default_escalation_topic(rules)?,
+            create_default_escalation_topic(rules),
         );
     }
     if !has_topic_by_name(nga, "off_topic") && !has_topic_by_name(nga, "offtopic") {
         nga.topics.insert(
             "topic off_topic".to_string(),
-            create_default_off_topic(rules)?,
+            create_default_off_topic(rules),
         );
     }
     if !has_topic_by_name(nga, "ambiguous") {
         nga.topics.insert(
             "topic ambiguous_question".to_string(),
-            create_default_ambiguous_topic(rules)?,
+            create_default_ambiguous_topic(rules),
         );
     }
-    Ok(())
 }
 
 /// Check if topic exists by name
@@ -1535,8 +2147,8 @@ fn has_topic_by_name(nga: &NGAOutput, name: &str) -> bool {
 
 /// Create default escalation topic
 fn create_default_escalation_topic(
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
+    rules: Option<&ConversionRules>,
+) -> Topic {
     let template = if let Some(rules) = rules {
         rules.templates.as_ref().and_then(|t| t.escalation.as_ref())
     } else {
@@ -1560,7 +2172,7 @@ fn create_default_escalation_topic(
             "If a user explicitly asks to transfer to a live agent, escalate the conversation.\nIf escalation to a live agent fails for any reason, acknowledge the issue and ask the user whether they would like to log a support case instead.".to_string()
         });
     
-    let mut actions = HashMap::new();
+    let mut actions = IndexMap::new();
     if let Some(template) = template {
         if let Some(reasoning) = &template.reasoning {
             if let Some(template_actions) = &reasoning.actions {
@@ -1590,6 +2202,9 @@ fn create_default_escalation_topic(
                             target,
                             description,
                             with_params: None,
+                            set_clauses: None,
+                            condition: None,
+                            comments: None,
                         },
                     );
                 }
@@ -1598,30 +2213,34 @@ fn create_default_escalation_topic(
     }
     
     // Ensure escalate action exists
-    if !actions.contains_key("escalate_to_human") {
+    if !actions.contains_key(ESCALATION_ACTION_NAME) {
         actions.insert(
-            "escalate_to_human".to_string(),
+            ESCALATION_ACTION_NAME.to_string(),
             ReasoningAction {
-                target: "@utils.escalate".to_string(),
-                description: Some("Call this tool to escalate to a human agent.".to_string()),
+                target: ESCALATE_TARGET.to_string(),
+                description: Some(ESCALATION_ACTION_DESCRIPTION.to_string()),
                 with_params: None,
+                set_clauses: None,
+                condition: None,
+                comments: None,
             },
         );
     }
     
-    Ok(Topic {
+    Topic {
         label: default_label,
         description: default_desc,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions: default_instructions,
             actions: Some(actions),
         },
         actions: None,
-    })
+    }
 }
 
 /// Create default off-topic topic
-fn create_default_off_topic(rules: &Option<ConversionRules>) -> Result<Topic, String> {
+fn create_default_off_topic(rules: Option<&ConversionRules>) -> Topic {
     let template = if let Some(rules) = rules {
         rules.templates.as_ref().and_then(|t| t.off_topic.as_ref())
     } else {
@@ -1663,21 +2282,22 @@ fn create_default_off_topic(rules: &Option<ConversionRules>) -> Result<Topic, St
         }
     }
     
-    Ok(Topic {
+    Topic {
         label: default_label,
         description: default_desc,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions,
-            actions: Some(HashMap::new()),
+            actions: Some(IndexMap::new()),
         },
         actions: None,
-    })
+    }
 }
 
 /// Create default ambiguous question topic
 fn create_default_ambiguous_topic(
-    rules: &Option<ConversionRules>,
-) -> Result<Topic, String> {
+    rules: Option<&ConversionRules>,
+) -> Topic {
     let template = if let Some(rules) = rules {
         rules
             .templates
@@ -1722,21 +2342,27 @@ fn create_default_ambiguous_topic(
         }
     }
     
-    Ok(Topic {
+    Topic {
         label: default_label,
         description: default_desc,
+        classification_description: None,
         reasoning: ReasoningSection {
             instructions,
-            actions: Some(HashMap::new()),
+            actions: Some(IndexMap::new()),
         },
         actions: None,
-    })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Parameter names the conversion gives the properties of an input or output type
+    fn property_names(io_type: &InputOutputType, prefix: &str) -> Vec<String> {
+        parameter_names(io_type, prefix, "param", "", &mut Vec::new())
+    }
+
     #[test]
     fn test_build_detailed_inputs_extracts_complex_data_type_name_from_lightning_type() {
         // Test that lightning:type is correctly extracted as complex_data_type_name
@@ -1764,7 +2390,7 @@ mod tests {
         let input_type: InputOutputType = serde_json::from_str(json_str).unwrap();
         let rules: Option<ConversionRules> = None;
         
-        let inputs = build_detailed_inputs(&input_type, &rules);
+        let inputs = build_detailed_inputs(&input_type, &property_names(&input_type, "Input:"), rules.as_ref());
         
         // Verify caseRecord has complex_data_type_name from lightning:type
         assert!(inputs.contains_key("caseRecord"), "Should contain 'caseRecord' input");
@@ -1822,7 +2448,7 @@ mod tests {
         let output_type: InputOutputType = serde_json::from_str(json_str).unwrap();
         let rules: Option<ConversionRules> = None;
         
-        let outputs = build_detailed_outputs(&output_type, &rules);
+        let outputs = build_detailed_outputs(&output_type, &property_names(&output_type, "Output:"), rules.as_ref());
         
         // Verify contactRecord has complex_data_type_name from lightning:type
         assert!(outputs.contains_key("contactRecord"), "Should contain 'contactRecord' output");
@@ -1870,7 +2496,7 @@ mod tests {
         let input_type: InputOutputType = serde_json::from_str(json_str).unwrap();
         let rules: Option<ConversionRules> = None;
         
-        let inputs = build_detailed_inputs(&input_type, &rules);
+        let inputs = build_detailed_inputs(&input_type, &property_names(&input_type, "Input:"), rules.as_ref());
         
         assert!(inputs.contains_key("queryInput"), "Should contain 'queryInput' input");
         let query_input = &inputs["queryInput"];
@@ -1911,7 +2537,7 @@ mod tests {
         let input_type: InputOutputType = serde_json::from_str(json_str).unwrap();
         let rules: Option<ConversionRules> = None;
         
-        let inputs = build_detailed_inputs(&input_type, &rules);
+        let inputs = build_detailed_inputs(&input_type, &property_names(&input_type, "Input:"), rules.as_ref());
         
         // Should contain "query" but NOT "mode" or "retrieverMode"
         assert!(inputs.contains_key("query"), "Should contain 'query' input");
@@ -1935,7 +2561,7 @@ mod tests {
         let input_type: InputOutputType = serde_json::from_str(json_str).unwrap();
         let rules: Option<ConversionRules> = None;
         
-        let inputs = build_detailed_inputs(&input_type, &rules);
+        let inputs = build_detailed_inputs(&input_type, &property_names(&input_type, "Input:"), rules.as_ref());
         
         // Should include inputs that don't have is_user_input specified (defaults to true)
         assert!(inputs.contains_key("unspecifiedInput"), "Should contain input without is_user_input (defaults to true)");
@@ -1958,6 +2584,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -1984,6 +2611,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2010,6 +2638,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2036,6 +2665,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2062,6 +2692,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: Some(vec![
                 Plugin {
                     name: "PTO_Management".to_string(),
@@ -2071,8 +2702,11 @@ mod tests {
                     scope: None,
                     plugin_type: Some("TOPIC".to_string()),
                     instruction_definitions: None,
+                    utterance_definitions: None,
                     functions: None,
                     can_escalate: None,
+                    rule_expressions: None,
+                    workflow_config: None,
                 },
             ]),
             topics: None,
@@ -2101,6 +2735,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2127,6 +2762,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2153,6 +2789,7 @@ mod tests {
             welcome_message_alt: None,
             user_location: None,
             voice_config: None,
+            adl_planner_configurations: None,
             plugins: None,
             topics: None,
             variables: None,
@@ -2161,4 +2798,397 @@ mod tests {
         let agent_type = detect_agent_type(&input);
         assert_eq!(agent_type, "AgentforceEmployeeAgent", "Employee agent in name should be detected");
     }
+
+    #[test]
+    fn test_conversion_keeps_source_order() {
+        let input: AgentforceInput = serde_json::from_str(r#"{"plugins": [
+            {"name": "Orders", "pluginType": "TOPIC", "functions": [
+                {"name": "GetOrder", "inputType": {"properties": {
+                    "orderId": {"type": "string"}, "customerId": {"type": "string"}
+                }}},
+                {"name": "CancelOrder"}
+            ]},
+            {"name": "Billing", "pluginType": "TOPIC"}
+        ]}"#).unwrap();
+        let (nga, _) = detect_and_convert(&input, None);
+
+        assert_eq!(nga.topics.keys().filter(|k| k.starts_with("topic ")).take(2).collect::<Vec<_>>(), ["topic orders", "topic billing"]);
+        let topic = &nga.topics["topic orders"];
+        assert_eq!(topic.actions.as_ref().unwrap().keys().collect::<Vec<_>>(), ["GetOrder", "CancelOrder"]);
+        assert_eq!(topic.reasoning.actions.as_ref().unwrap().keys().collect::<Vec<_>>(), ["GetOrder", "CancelOrder"]);
+        let inputs = topic.actions.as_ref().unwrap()["GetOrder"].inputs.as_ref().unwrap();
+        assert_eq!(inputs.keys().collect::<Vec<_>>(), ["orderId", "customerId"]);
+    }
+
+    #[test]
+    fn test_knowledge_from_adl_planner_configuration() {
+        let outcome = crate::convert_json(r#"{
+            "adlPlannerConfigurations": [{"ragFeatureName": "FAQ_Library"}],
+            "plugins": [{
+                "name": "GeneralFAQ",
+                "pluginType": "TOPIC",
+                "functions": [{
+                    "name": "AnswerQuestionsWithKnowledge",
+                    "inputType": {"properties": {"citationsEnabled": {"type": "boolean", "const": false}}}
+                }]
+            }]
+        }"#, None, None).unwrap();
+        assert_eq!(outcome.nga.knowledge.rag_feature_config_id, "FAQ_Library");
+        assert!(!outcome.nga.knowledge.citations_enabled);
+
+        assert!(outcome.yaml.contains("\n    rag_feature_config_id: \"FAQ_Library\"\n"));
+    }
+
+    #[test]
+    fn test_knowledge_prefers_library_bound_by_function() {
+        let input: AgentforceInput = serde_json::from_str(r#"{
+            "adlPlannerConfigurations": [
+                {"ragFeatureName": "Library_A"},
+                {"ragFeatureName": "Library_B"}
+            ],
+            "plugins": [{
+                "name": "GeneralFAQ",
+                "functions": [{
+                    "name": "AnswerQuestionsWithKnowledge",
+                    "inputType": {"properties": {
+                        "ragFeatureConfigId": {"type": "string", "const": "Library_B"},
+                        "citationsEnabled": {"type": "boolean", "const": true}
+                    }}
+                }]
+            }]
+        }"#).unwrap();
+
+        let selection = resolve_knowledge(&input);
+        assert_eq!(selection.candidates, ["Library_A", "Library_B"]);
+        assert_eq!(selection.knowledge.rag_feature_config_id, "Library_B");
+        assert!(selection.knowledge.citations_enabled);
+    }
+
+    #[test]
+    fn test_const_input_values_are_bound() {
+        let input: AgentforceInput = serde_json::from_str(r#"{"plugins": [{"name": "FAQ", "pluginType": "TOPIC", "functions": [{
+            "name": "AnswerQuestions",
+            "inputType": {"properties": {
+                "query": {"type": "string"},
+                "ragFeatureConfigId": {"type": "string", "const": "FAQ_Library"}
+            }}
+        }]}]}"#).unwrap();
+        let (nga, _) = detect_and_convert(&input, None);
+
+        let topic = &nga.topics["topic faq"];
+        let inputs = topic.actions.as_ref().unwrap()["AnswerQuestions"].inputs.as_ref().unwrap();
+        assert_eq!(inputs["ragFeatureConfigId"].const_value, Some(serde_json::json!("FAQ_Library")));
+        assert_eq!(inputs["query"].const_value, None);
+        let with_params = topic.reasoning.actions.as_ref().unwrap()["AnswerQuestions"].with_params.as_ref().unwrap();
+        let binding = with_params.iter().find(|param| param.name == "ragFeatureConfigId").map(|param| &param.binding);
+        assert_eq!(binding, Some(&WithBinding::Literal(serde_json::json!("FAQ_Library"))));
+    }
+
+    #[test]
+    fn test_bind_input_prefers_const_then_variable() {
+        let input = |const_value: Option<serde_json::Value>, is_user_input: bool| ActionInputDef {
+            input_type: "string".to_string(),
+            const_value,
+            description: None,
+            label: None,
+            is_required: false,
+            is_user_input,
+            complex_data_type_name: None,
+        };
+        let mut variables = IndexMap::new();
+        variables.insert("ContactId".to_string(), Variable {
+            var_type: "mutable string".to_string(),
+            label: None,
+            source: None,
+            description: "Contact".to_string(),
+        });
+
+        assert_eq!(bind_input("contactId", &input(Some(serde_json::json!("003")), true), &variables),
+            Some(WithBinding::Literal(serde_json::json!("003"))));
+        assert_eq!(bind_input("contactId", &input(None, false), &variables),
+            Some(WithBinding::Variable("ContactId".to_string())));
+        assert_eq!(bind_input("query", &input(None, true), &variables), Some(WithBinding::SlotFill));
+        assert_eq!(bind_input("mode", &input(None, false), &variables), None);
+        assert_eq!(bind_input("filters", &input(Some(serde_json::json!({"a": 1})), true), &variables),
+            Some(WithBinding::SlotFill));
+    }
+
+    #[test]
+    fn test_set_clauses_from_outputs_feeding_mutable_variables() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Cases",
+                "pluginType": "TOPIC",
+                "scope": "Summarize {!$CaseRecord} and quote {!$CaseNumber}.",
+                "functions": [{
+                    "name": "GetCase",
+                    "invocationTargetType": "flow",
+                    "invocationTargetName": "Get_Case",
+                    "outputType": {"properties": {
+                        "CaseRecord": {"type": "object", "lightning:type": "lightning__recordInfoType"},
+                        "CaseNumber": {"type": "string"}
+                    }}
+                }]
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        assert_eq!(outcome.nga.variables["CaseNumber"].source.as_deref(), Some("@action.GetCase.CaseNumber"));
+
+        // Only the mutable variable is assigned; the linked one is populated by its source
+        let reasoning_actions = outcome.nga.topics["topic cases"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(reasoning_actions["GetCase"].set_clauses, Some(vec![SetClause {
+            variable: "CaseRecord".to_string(),
+            output: "CaseRecord".to_string(),
+        }]));
+        assert!(outcome.yaml.contains("            GetCase: @actions.GetCase\n                set @variables.CaseRecord = @outputs.CaseRecord\n"));
+
+        let rules = crate::parse_rules(r#"{"expressions": {"assignment": {"syntax": "set {variable} to {value}"}}}"#).unwrap();
+        let script = crate::yaml_generator::generate_nga_yaml(&outcome.nga, rules.as_ref());
+        assert!(script.contains("set @variables.CaseRecord to @outputs.CaseRecord\n"));
+        let parsed = crate::parse_agent_script(&script, rules.as_ref()).unwrap();
+        assert_eq!(parsed.topics["topic cases"].reasoning.actions.as_ref().unwrap()["GetCase"].set_clauses,
+            reasoning_actions["GetCase"].set_clauses);
+    }
+
+    #[test]
+    fn test_rule_expressions_become_conditions() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Cases",
+                "pluginType": "TOPIC",
+                "ruleExpressions": [{
+                    "expressionName": "Verified_Only",
+                    "conditions": [{"leftOperand": "{!$Verified}", "operator": "equal", "rightOperandValue": "true"}]
+                }],
+                "functions": [
+                    {"name": "GetCase", "invocationTargetType": "flow", "invocationTargetName": "Get_Case"},
+                    {
+                        "name": "CloseCase",
+                        "invocationTargetType": "flow",
+                        "invocationTargetName": "Close_Case",
+                        "ruleExpressions": [
+                            {"conditions": [{"leftOperand": "CaseCount", "operator": "greaterThan", "rightOperandValue": 0}]},
+                            {"expressionName": "Open_Status", "expression": "ISPICKVAL(Status, 'Open')"}
+                        ]
+                    }
+                ]
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        assert!(outcome.nga.variables.contains_key("Verified"));
+        assert!(outcome.nga.variables.contains_key("CaseCount"));
+
+        let selector = outcome.nga.topics["start_agent topic_selector"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(selector["go_to_cases"].condition.as_deref(), Some("@variables.Verified == True"));
+        let reasoning_actions = outcome.nga.topics["topic cases"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(reasoning_actions["GetCase"].condition, None);
+        assert_eq!(reasoning_actions["CloseCase"].condition.as_deref(), Some("@variables.CaseCount > 0"));
+        assert_eq!(reasoning_actions["CloseCase"].comments.as_ref().unwrap().len(), 1);
+
+        assert!(outcome.yaml.contains("            if @variables.Verified == True:\n                go_to_cases: @utils.transition to @topic.cases\n"));
+        assert!(outcome.yaml.contains(concat!(
+            "            GetCase: @actions.GetCase\n",
+            "            if @variables.CaseCount > 0:\n",
+            "                CloseCase: @actions.CloseCase\n",
+            "                    # Unsupported rule expression 'Open_Status': ",
+        )));
+
+        let parsed = crate::parse_agent_script(&outcome.yaml, None).unwrap();
+        let parsed_actions = parsed.topics["topic cases"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(parsed_actions["CloseCase"].condition, reasoning_actions["CloseCase"].condition);
+        assert_eq!(parsed_actions["CloseCase"].comments, reasoning_actions["CloseCase"].comments);
+    }
+
+    #[test]
+    fn test_function_workflow_config_becomes_comment() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Cases",
+                "pluginType": "TOPIC",
+                "functions": [{
+                    "name": "GetCase",
+                    "invocationTargetType": "flow",
+                    "invocationTargetName": "Get_Case",
+                    "workflowConfig": {"flow": "Route_Case"}
+                }]
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        let reasoning_actions = outcome.nga.topics["topic cases"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(reasoning_actions["GetCase"].condition, None);
+        assert_eq!(reasoning_actions["GetCase"].comments, Some(vec![
+            "Unsupported workflowConfig: {\"flow\":\"Route_Case\"}".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn test_utterances_become_classification_description() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Orders",
+                "pluginType": "TOPIC",
+                "utteranceDefinitions": [
+                    {"name": "u1", "utterance": " Where is my order? "},
+                    {"name": "u2", "utterance": "Track my package"},
+                    {"name": "u3", "utterance": "Where is my order?"},
+                    {"name": "u4", "utterance": ""}
+                ]
+            }, {
+                "name": "Billing",
+                "pluginType": "TOPIC",
+                "utteranceDefinitions": []
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        let expected = "Route here for requests like: \"Where is my order?\"; \"Track my package\"";
+        assert_eq!(outcome.nga.topics["topic orders"].classification_description.as_deref(), Some(expected));
+        assert_eq!(outcome.nga.topics["topic billing"].classification_description, None);
+
+        let selector = outcome.nga.topics["start_agent topic_selector"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(selector["go_to_orders"].description, Some(format!("Handles Orders requests {}", expected)));
+        assert_eq!(selector["go_to_billing"].description.as_deref(), Some("Handles Billing requests"));
+        assert!(outcome.yaml.contains(
+            "    classification_description: \"Route here for requests like: \\\"Where is my order?\\\"; \\\"Track my package\\\"\"\n"
+        ));
+
+        let parsed = crate::parse_agent_script(&outcome.yaml, None).unwrap();
+        assert_eq!(parsed.topics["topic orders"].classification_description.as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn test_transition_descriptions_from_template() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Orders",
+                "label": "Order Status",
+                "pluginType": "TOPIC",
+                "description": "  Order questions.  ",
+                "scope": "Only orders of {!$ContactName}."
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        let selector = outcome.nga.topics["start_agent topic_selector"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(
+            selector["go_to_orders"].description.as_deref(),
+            Some("Order questions. Only orders of {!@variables.ContactName}.")
+        );
+        // Default topics are described from their templates too
+        assert!(selector["go_to_escalation"].description.is_some());
+        assert!(outcome.yaml.contains(concat!(
+            "            go_to_orders: @utils.transition to @topic.orders\n",
+            "                description: \"Order questions. Only orders of {!@variables.ContactName}.\"\n",
+        )));
+
+        let rules = crate::parse_rules(
+            r#"{"templates": {"topic_selector": {"transition_description": "Go to {label} ({topic}): {description}"}}}"#
+        ).unwrap();
+        let outcome = crate::convert_json(agent, rules.as_ref(), None).unwrap();
+        let selector = outcome.nga.topics["start_agent topic_selector"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(
+            selector["go_to_orders"].description.as_deref(),
+            Some("Go to Order Status (orders): Order questions. Only orders of {!@variables.ContactName}.")
+        );
+    }
+
+    #[test]
+    fn test_can_escalate_adds_escalation_action() {
+        let agent = r#"{
+            "plugins": [
+                {"name": "Support", "pluginType": "TOPIC", "canEscalate": true},
+                {"name": "Orders", "pluginType": "TOPIC", "canEscalate": false},
+                {
+                    "name": "Billing",
+                    "pluginType": "TOPIC",
+                    "canEscalate": true,
+                    "functions": [{"name": "escalate_to_human", "invocationTargetType": "flow", "invocationTargetName": "Billing_Escalation"}]
+                }
+            ]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        let support = outcome.nga.topics["topic support"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(support[ESCALATION_ACTION_NAME].target, ESCALATE_TARGET);
+        assert!(outcome.nga.topics["topic orders"].reasoning.actions.is_none());
+
+        // A function already named like the escalation action keeps its reference
+        let billing = outcome.nga.topics["topic billing"].reasoning.actions.as_ref().unwrap();
+        assert_eq!(billing[ESCALATION_ACTION_NAME].target, "@actions.escalate_to_human");
+        assert!(outcome.yaml.contains("            escalate_to_human: @utils.escalate\n                description: \"Call this tool to escalate to a human agent.\"\n"));
+    }
+
+    #[test]
+    fn test_record_id_suffixes_removed_from_names() {
+        let agent = r#"{
+            "plugins": [
+                {
+                    "name": "General_Web_Search_16jKc0000004Cqw",
+                    "pluginType": "TOPIC",
+                    "functions": [
+                        {"name": "AnswerQuestionsWithKnowledge_179Kc0000004HuX"},
+                        {"name": "179Kc0000004HuY", "label": "Answer Questions"}
+                    ]
+                },
+                {"name": "16jKc0000004Cqx", "label": "Case Management", "pluginType": "TOPIC"}
+            ]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        assert!(outcome.nga.topics.contains_key("topic case_management"));
+        let actions = outcome.nga.topics["topic general_web_search"].actions.as_ref().unwrap();
+        assert!(actions.contains_key("AnswerQuestionsWithKnowledge"));
+        assert!(actions.contains_key("Answer_Questions"));
+
+        // Nothing usable left and no label to fall back to
+        let outcome = crate::convert_json(r#"{"plugins": [{"name": "16jKc0000004Cqw", "pluginType": "TOPIC"}]}"#, None, None).unwrap();
+        assert!(outcome.nga.topics.contains_key("topic topic"));
+        assert_eq!(outcome.renames[0].original, "16jKc0000004Cqw");
+    }
+
+    #[test]
+    fn test_action_targets_from_rules() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Orders",
+                "pluginType": "TOPIC",
+                "functions": [
+                    {"name": "GetOrder", "invocationTargetType": "flow", "invocationTargetName": "Get_Order"},
+                    {"name": "Search", "invocationTargetType": "standardInvocableAction", "invocationTargetName": "EmployeeCopilot__Search"},
+                    {"name": "Notify", "invocationTargetType": "externalService", "invocationTargetName": "Notify_Customer"}
+                ]
+            }]
+        }"#;
+        let outcome = crate::convert_json(agent, None, None).unwrap();
+        let actions = outcome.nga.topics["topic orders"].actions.as_ref().unwrap();
+        assert_eq!(actions["GetOrder"].target, "flow://Get_Order");
+        assert_eq!(actions["Notify"].target, "externalService://Notify_Customer");
+        assert_eq!(outcome.warnings, [Diagnostic::warning(
+            "plugins[0].functions[2].invocationTargetType",
+            "Invocation type 'externalService' has no mapping in target_format.mappings; its target uses it as is",
+        )]);
+
+        let rules = crate::parse_rules(r#"{"target_format": {
+            "syntax": "{invocation_type}:{target_name}",
+            "mappings": {"flow": "flow", "standardInvocableAction": "standard", "externalService": "external"},
+            "name_transforms": {"standardInvocableAction": "strip_namespace", "flow": "snake_case"}
+        }}"#).unwrap();
+        let outcome = crate::convert_json(agent, rules.as_ref(), None).unwrap();
+        let actions = outcome.nga.topics["topic orders"].actions.as_ref().unwrap();
+        assert_eq!(actions["GetOrder"].target, "flow:get_order");
+        assert_eq!(actions["Search"].target, "standard:Search");
+        assert_eq!(actions["Notify"].target, "external:Notify_Customer");
+        assert!(outcome.warnings.is_empty());
+
+        // The simple format builds targets the same way and keeps full targets
+        let simple = r#"{
+            "topics": [{
+                "name": "Orders",
+                "actions": [
+                    {"name": "GetOrder", "type": "flow", "invocation_target": "Get_Order"},
+                    {"name": "Lookup", "invocation_target": "apex://Lookup_Order"}
+                ]
+            }]
+        }"#;
+        let outcome = crate::convert_json(simple, rules.as_ref(), None).unwrap();
+        let actions = outcome.nga.topics["topic orders"].actions.as_ref().unwrap();
+        assert_eq!(actions["GetOrder"].target, "flow:get_order");
+        assert_eq!(actions["Lookup"].target, "apex://Lookup_Order");
+    }
 }
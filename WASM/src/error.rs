@@ -0,0 +1,158 @@
+//! Errors returned by the crate's public API
+//!
+//! Parsing an agent definition, conversion rules or an ID map fails with a
+//! `ConvertError` naming the JSON path of the offending element; parsing Agent
+//! Script text fails with a `ScriptError` naming its line and column. The
+//! conversion itself cannot fail.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// ============================================================================
+// ERROR TYPES
+// ============================================================================
+
+/// Category of a parse error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The agent definition could not be parsed or has the wrong shape
+    InvalidInput,
+    /// The conversion rules could not be parsed or have the wrong shape
+    InvalidRules,
+}
+
+/// Error returned by the conversion API
+///
+/// `path` is the JSON path of the offending element in the source document
+/// (e.g. `plugins[3].functions[1].inputType`), or empty when the error is not
+/// tied to a single element. Serializes to `{ kind, path, message }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConvertError {
+    pub kind: ErrorKind,
+    pub path: String,
+    pub message: String,
+}
+
+impl ConvertError {
+    /// Create an error for the element at `path`
+    pub fn new(kind: ErrorKind, path: impl Into<String>, message: impl Into<String>) -> Self {
+        ConvertError {
+            kind,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Build an error from a deserialization failure, keeping the failing path
+    pub fn from_path_error<E: fmt::Display>(
+        kind: ErrorKind,
+        context: &str,
+        err: serde_path_to_error::Error<E>,
+    ) -> Self {
+        let path = err.path().to_string();
+        let path = if path == "." { String::new() } else { path };
+        ConvertError::new(kind, path, format!("{}: {}", context, err.inner()))
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} (at {})", self.message, self.path)
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Error returned when Agent Script text cannot be parsed
+///
+/// `line` and `column` are 1-based positions in the script text.
+/// Serializes to `{ line, column, message }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ScriptError {
+    /// Create an error at `line`/`column`
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ScriptError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+// ============================================================================
+// JSON PATH HELPERS
+// ============================================================================
+
+/// Append an object field to a JSON path
+pub fn path_field(base: &str, field: &str) -> String {
+    if base.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", base, field)
+    }
+}
+
+/// Append an array index to a JSON path
+pub fn path_index(base: &str, index: usize) -> String {
+    format!("{}[{}]", base, index)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_helpers() {
+        let plugin = path_index("plugins", 3);
+        let function = path_index(&path_field(&plugin, "functions"), 1);
+        assert_eq!(path_field(&function, "inputType"), "plugins[3].functions[1].inputType");
+        assert_eq!(path_field("", "name"), "name");
+    }
+
+    #[test]
+    fn test_display_includes_path() {
+        let err = ConvertError::new(ErrorKind::InvalidInput, "plugins[0].name", "Topic name is empty");
+        assert_eq!(err.to_string(), "Topic name is empty (at plugins[0].name)");
+
+        let err = ConvertError::new(ErrorKind::InvalidRules, "", "Failed to parse rules JSON");
+        assert_eq!(err.to_string(), "Failed to parse rules JSON");
+    }
+
+    #[test]
+    fn test_script_error_display() {
+        let err = ScriptError::new(12, 5, "Unknown field 'foo'");
+        assert_eq!(err.to_string(), "line 12, column 5: Unknown field 'foo'");
+    }
+
+    #[test]
+    fn test_serializes_as_object() {
+        let err = ConvertError::new(ErrorKind::InvalidInput, "topics[1].name", "Topic name is empty");
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "invalid_input");
+        assert_eq!(value["path"], "topics[1].name");
+        assert_eq!(value["message"], "Topic name is empty");
+    }
+}
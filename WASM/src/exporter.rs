@@ -0,0 +1,587 @@
+use indexmap::IndexMap;
+use crate::models::*;
+use crate::helpers::{DEFAULT_TARGET_SYNTAX, ESCALATE_TARGET};
+use crate::salesforce_id::is_salesforce_id;
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+/// Prefix of the default agent user generated by the converter
+const AGENT_USER_PREFIX: &str = "agentforce_service_agent@";
+
+/// Suffix of the default agent user generated by the converter
+const AGENT_USER_SUFFIX: &str = ".ext";
+
+// ============================================================================
+// AGENT EXPORT
+// ============================================================================
+
+/// Export an NGA agent back to the Salesforce Agentforce export shape
+///
+/// Every `topic <name>` becomes a `TOPIC` plugin whose `instructionDefinitions`
+/// are the lines of its reasoning instructions and whose `functions` are its
+/// detailed actions. The `start_agent` topic selector is not exported since the
+/// converter rebuilds it from the plugins. Plugins, functions, properties and
+/// variables keep the order of the NGA model.
+pub fn export_to_agentforce(nga: &NGAOutput, rules: Option<&ConversionRules>) -> AgentforceInput {
+    let plugins = nga
+        .topics
+        .iter()
+        .filter_map(|(key, topic)| {
+            key.strip_prefix("topic ")
+                .map(|name| export_topic(name, topic, rules))
+        })
+        .collect();
+
+    let variables: Vec<VariableInput> = nga
+        .variables
+        .iter()
+        .map(|(name, variable)| export_variable(name, variable))
+        .collect();
+
+    let secondary_locales: Vec<String> = nga
+        .language
+        .additional_locales
+        .split(',')
+        .map(|locale| locale.trim().to_string())
+        .filter(|locale| !locale.is_empty())
+        .collect();
+
+    AgentforceInput {
+        id: extract_agent_id(&nga.config.default_agent_user),
+        name: non_empty(&nga.config.developer_name),
+        label: non_empty(&nga.config.agent_label),
+        description: non_empty(&nga.config.description),
+        planner_role: non_empty(&nga.system.instructions),
+        planner_company: None,
+        planner_tone_type: None,
+        locale: non_empty(&nga.language.default_locale),
+        secondary_locales: if secondary_locales.is_empty() { None } else { Some(secondary_locales) },
+        welcome_message: non_empty(&nga.system.messages.welcome),
+        welcome_message_alt: None,
+        user_location: None,
+        voice_config: nga
+            .connections
+            .contains_key("connection voice")
+            .then(|| serde_json::Value::Object(serde_json::Map::new())),
+        adl_planner_configurations: non_empty(&nga.knowledge.rag_feature_config_id)
+            .map(|config_id| vec![export_planner_configuration(config_id)]),
+        plugins: Some(plugins),
+        topics: None,
+        variables: if variables.is_empty() { None } else { Some(variables) },
+    }
+}
+
+/// Export a single NGA topic as a TOPIC plugin
+fn export_topic(name: &str, topic: &Topic, rules: Option<&ConversionRules>) -> Plugin {
+    let instructions = strip_security_rules(&topic.reasoning.instructions, rules);
+    let instruction_definitions: Vec<InstructionDefinition> = instructions
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| InstructionDefinition {
+            name: Some(format!("instruction_{}", index + 1)),
+            description: Some(line.to_string()),
+        })
+        .collect();
+
+    let functions = topic.actions.as_ref().map(|actions| {
+        actions
+            .iter()
+            .map(|(action_name, action)| export_action(action_name, action, rules))
+            .collect()
+    });
+
+    let can_escalate = topic
+        .reasoning
+        .actions
+        .as_ref()
+        .map(|actions| actions.values().any(|a| a.target.starts_with(ESCALATE_TARGET)))
+        .unwrap_or(false);
+
+    Plugin {
+        name: name.to_string(),
+        local_dev_name: Some(name.to_string()),
+        label: non_empty(&topic.label),
+        description: non_empty(&topic.description),
+        scope: None,
+        plugin_type: Some("TOPIC".to_string()),
+        instruction_definitions: if instruction_definitions.is_empty() { None } else { Some(instruction_definitions) },
+        utterance_definitions: None,
+        functions,
+        can_escalate: Some(can_escalate),
+        rule_expressions: None,
+        workflow_config: None,
+    }
+}
+
+/// Export the knowledge block's RAG feature as a planner configuration
+/// The converter reads the feature name, falling back to its record ID, so
+/// record IDs go back to `ragFeatureId` and names to `ragFeatureName`
+fn export_planner_configuration(config_id: String) -> AdlPlannerConfiguration {
+    let (rag_feature_id, rag_feature_name) = if is_salesforce_id(&config_id) {
+        (Some(config_id), None)
+    } else {
+        (None, Some(config_id))
+    };
+    AdlPlannerConfiguration {
+        rag_feature_id,
+        rag_feature_name,
+        rag_feature_fully_qualified_name: None,
+        adl_configuration: None,
+    }
+}
+
+/// Remove the security rules block the converter appends to some topics
+/// The converter adds it again when the exported agent is converted
+fn strip_security_rules<'a>(instructions: &'a str, rules: Option<&ConversionRules>) -> &'a str {
+    let default_rules = rules
+        .and_then(|r| r.security_rules.as_ref())
+        .and_then(|s| s.default_rules.as_ref());
+
+    if let Some(default_rules) = default_rules {
+        if !default_rules.is_empty() {
+            let mut block = String::from("Rules:");
+            for rule in default_rules {
+                block.push_str(&format!("\n  {}", rule));
+            }
+            if let Some(stripped) = instructions.strip_suffix(block.as_str()) {
+                return stripped.trim_end_matches('\n');
+            }
+        }
+    }
+
+    instructions
+}
+
+/// Split a target built with `target_format.syntax` into its invocation type and name
+/// The scheme is mapped back through `target_format.mappings`; name transforms cannot be undone
+fn split_action_target(target: &str, rules: Option<&ConversionRules>) -> Option<(String, String)> {
+    let target_format = rules.and_then(|r| r.target_format.as_ref());
+    let syntax = target_format
+        .and_then(|f| f.syntax.as_deref())
+        .filter(|s| s.contains("{target_name}"))
+        .unwrap_or(DEFAULT_TARGET_SYNTAX);
+    let pattern = format!(
+        "^{}$",
+        regex::escape(syntax)
+            .replace(r"\{invocation_type\}", "(?P<type>.+?)")
+            .replace(r"\{target_name\}", "(?P<name>.+)")
+    );
+    let captures = regex::Regex::new(&pattern).ok()?.captures(target)?;
+    let scheme = captures.name("type")?.as_str();
+    let name = captures.name("name")?.as_str().to_string();
+
+    let target_type = target_format
+        .and_then(|f| f.mappings.as_ref())
+        .and_then(|mappings| {
+            let mut types: Vec<_> = mappings.iter().filter(|(_, mapped)| *mapped == scheme).map(|(t, _)| t).collect();
+            // Prefer the type named like the scheme when several map to it
+            types.sort_by_key(|t| (t.as_str() != scheme, t.as_str()));
+            types.first().map(|t| t.to_string())
+        })
+        .unwrap_or_else(|| scheme.to_string());
+    Some((target_type, name))
+}
+
+/// Export a detailed action as a plugin function
+fn export_action(name: &str, action: &Action, rules: Option<&ConversionRules>) -> Function {
+    let (target_type, target_name) = match split_action_target(&action.target, rules) {
+        Some((target_type, target_name)) => (Some(target_type), Some(target_name)),
+        None => (None, non_empty(&action.target)),
+    };
+
+    Function {
+        name: name.to_string(),
+        local_dev_name: Some(name.to_string()),
+        label: action.label.clone(),
+        description: non_empty(&action.description),
+        invocation_target_type: target_type,
+        invocation_target_name: target_name,
+        invocation_target_id: None,
+        input_type: action.inputs.as_ref().map(|inputs| export_inputs(inputs, rules)),
+        output_type: action.outputs.as_ref().map(|outputs| export_outputs(outputs, rules)),
+        require_user_confirmation: Some(action.require_user_confirmation),
+        include_in_progress_indicator: Some(action.include_in_progress_indicator),
+        progress_indicator_message: action.progress_indicator_message.clone(),
+        source: action.source.clone(),
+        rule_expressions: None,
+        workflow_config: None,
+    }
+}
+
+/// Export action inputs as a JSON-schema style inputType
+fn export_inputs(inputs: &IndexMap<String, ActionInputDef>, rules: Option<&ConversionRules>) -> InputOutputType {
+    let mut properties = IndexMap::new();
+    let mut required = Vec::new();
+
+    for (name, input) in inputs {
+        let mut property = export_property(&input.input_type, input.complex_data_type_name.as_deref(), rules);
+        property.title = input.label.clone();
+        property.description = input.description.clone();
+        property.const_value = input.const_value.clone();
+        property.is_user_input = Some(input.is_user_input);
+
+        if input.is_required {
+            required.push(name.clone());
+        }
+        properties.insert(name.clone(), property);
+    }
+
+    InputOutputType {
+        properties: Some(properties),
+        required: Some(required),
+    }
+}
+
+/// Export action outputs as a JSON-schema style outputType
+fn export_outputs(outputs: &IndexMap<String, ActionOutputDef>, rules: Option<&ConversionRules>) -> InputOutputType {
+    let mut properties = IndexMap::new();
+
+    for (name, output) in outputs {
+        let mut property = export_property(&output.output_type, output.complex_data_type_name.as_deref(), rules);
+        property.title = output.label.clone();
+        property.description = output.description.clone();
+        property.is_displayable = Some(output.is_displayable);
+        property.is_used_by_planner = Some(output.is_used_by_planner);
+
+        properties.insert(name.clone(), property);
+    }
+
+    InputOutputType {
+        properties: Some(properties),
+        required: None,
+    }
+}
+
+/// Build a JSON-schema property for an NGA type, carrying its lightning type
+fn export_property(nga_type: &str, complex_type: Option<&str>, rules: Option<&ConversionRules>) -> Property {
+    let mut property = empty_property(Some(reverse_map_type(nga_type, rules)));
+
+    if let Some(item_type) = nga_type.strip_prefix("list[").and_then(|t| t.strip_suffix(']')) {
+        property.items = Some(Box::new(empty_property(Some(reverse_map_type(item_type, rules)))));
+    }
+
+    if let Some(complex_type) = complex_type {
+        property.lightning_type = Some(complex_type.to_string());
+        property.ref_type = Some(format!("#/$defs/{}", complex_type));
+    }
+
+    property
+}
+
+/// Create a property with only its JSON type set
+fn empty_property(prop_type: Option<String>) -> Property {
+    Property {
+        prop_type,
+        title: None,
+        description: None,
+        items: None,
+        const_value: None,
+        is_user_input: None,
+        is_displayable: None,
+        is_used_by_planner: None,
+        lightning_type: None,
+        ref_type: None,
+        default_value: None,
+    }
+}
+
+/// Map an NGA type back to its JSON-schema type using the rules' type mappings
+fn reverse_map_type(nga_type: &str, rules: Option<&ConversionRules>) -> String {
+    if nga_type.starts_with("list[") {
+        return "array".to_string();
+    }
+
+    if let Some(rules) = rules {
+        if let Some(type_mappings) = &rules.type_mappings {
+            if let Some(primitive) = &type_mappings.primitive {
+                // Prefer the JSON type that maps to itself (e.g. number over integer)
+                let mut candidates: Vec<_> = primitive
+                    .iter()
+                    .filter(|(_, mapped)| mapped.as_str() == nga_type)
+                    .map(|(json_type, _)| json_type.as_str())
+                    .collect();
+                candidates.sort_by_key(|json_type| (*json_type != nga_type, *json_type));
+                if let Some(json_type) = candidates.first() {
+                    return json_type.to_string();
+                }
+            }
+        }
+    }
+
+    match nga_type {
+        "string" | "number" | "boolean" => nga_type.to_string(),
+        _ => "object".to_string(),
+    }
+}
+
+/// Export an NGA variable definition
+fn export_variable(name: &str, variable: &Variable) -> VariableInput {
+    VariableInput {
+        name: Some(name.to_string()),
+        id: None,
+        label: variable.label.clone(),
+        var_type: Some(variable.var_type.clone()),
+        source: variable.source.clone(),
+        description: non_empty(&variable.description),
+    }
+}
+
+/// Recover the agent ID from a generated default agent user
+fn extract_agent_id(default_agent_user: &str) -> Option<String> {
+    default_agent_user
+        .strip_prefix(AGENT_USER_PREFIX)
+        .and_then(|rest| rest.strip_suffix(AGENT_USER_SUFFIX))
+        .filter(|id| !id.is_empty() && *id != "example")
+        .map(|id| id.to_string())
+}
+
+/// Treat empty strings as absent values
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() { None } else { Some(value.to_string()) }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::*;
+    use serde_json::Value;
+    use std::collections::BTreeSet;
+
+    const SAMPLE_AGENT: &str = include_str!("../../website/agent.json");
+    const SAMPLE_RULES: &str = include_str!("../../website/nga-rules.json");
+
+    /// Fields of website/agent.json that do not survive convert -> export
+    /// - agent: planner company/tone/location are merged into the system
+    ///   instructions, names are upper-cased developer names, descriptions are cleaned
+    /// - plugins/functions: ID suffixes are stripped from names, scope is merged into
+    ///   description and instructions, instruction names are regenerated, target IDs
+    ///   are dropped
+    /// - parameters: `Input:`/`Output:` prefixes and non-user inputs are dropped,
+    ///   rich text and list types are normalized to object/recordInfo types
+    const KNOWN_LOSSY_FIELDS: &[&str] = &[
+        "adlPlannerConfigurations[].adlConfiguration changed",
+        "adlPlannerConfigurations[].ragFeatureFullyQualifiedName changed",
+        "adlPlannerConfigurations[].ragFeatureId changed",
+        "description changed",
+        "name changed",
+        "plannerCompany changed",
+        "plannerRole changed",
+        "plannerToneType changed",
+        "plugins[].description changed",
+        "plugins[].functions[].inputType.properties.* dropped",
+        "plugins[].functions[].inputType.required changed",
+        "plugins[].functions[].invocationTargetId changed",
+        "plugins[].functions[].name changed",
+        "plugins[].functions[].outputType.properties.*.$ref changed",
+        "plugins[].functions[].outputType.properties.*.items.$ref changed",
+        "plugins[].functions[].outputType.properties.*.items.lightning:type changed",
+        "plugins[].functions[].outputType.properties.*.items.type changed",
+        "plugins[].functions[].outputType.properties.*.lightning:type changed",
+        "plugins[].functions[].outputType.properties.*.type changed",
+        "plugins[].functions[].ruleExpressions changed",
+        "plugins[].instructionDefinitions changed",
+        "plugins[].localDevName changed",
+        "plugins[].name changed",
+        "plugins[].ruleExpressions changed",
+        "plugins[].scope changed",
+        "plugins[].utteranceDefinitions changed",
+        "userLocation changed",
+        "welcomeMessage changed",
+    ];
+
+    /// Collect the generalized paths where two JSON values differ
+    /// Array indices become `[]` and property names become `*`
+    fn collect_differences(original: &Value, exported: &Value, path: &str, out: &mut BTreeSet<String>) {
+        match (original, exported) {
+            (Value::Object(left), Value::Object(right)) => {
+                let under_properties = path.ends_with("properties");
+                for key in left.keys().chain(right.keys()) {
+                    let segment = if under_properties { "*" } else { key.as_str() };
+                    let child_path = if path.is_empty() { segment.to_string() } else { format!("{}.{}", path, segment) };
+                    match (left.get(key), right.get(key)) {
+                        (Some(l), Some(r)) => collect_differences(l, r, &child_path, out),
+                        (Some(Value::Null), None) | (None, Some(Value::Null)) | (None, None) => {}
+                        (Some(_), None) => {
+                            out.insert(format!("{} dropped", child_path));
+                        }
+                        (None, Some(_)) => {
+                            out.insert(format!("{} added", child_path));
+                        }
+                    }
+                }
+            }
+            (Value::Array(left), Value::Array(right)) if left.len() == right.len() => {
+                for (l, r) in left.iter().zip(right) {
+                    collect_differences(l, r, &format!("{}[]", path), out);
+                }
+            }
+            _ => {
+                if original != exported {
+                    out.insert(format!("{} changed", path));
+                }
+            }
+        }
+    }
+
+    fn plugin_key(plugin: &Plugin) -> String {
+        sanitize_topic_name(plugin.local_dev_name.as_deref().or(Some(plugin.name.as_str())))
+    }
+
+    #[test]
+    fn test_round_trip_preserves_generated_script() {
+        let rules = crate::parse_rules(SAMPLE_RULES).unwrap();
+        let first = crate::convert_json(SAMPLE_AGENT, rules.as_ref(), None).unwrap();
+
+        let exported = export_to_agentforce(&first.nga, rules.as_ref());
+        let second = crate::convert(&exported, rules.as_ref(), None);
+
+        assert_eq!(second.yaml, first.yaml);
+    }
+
+    #[test]
+    fn test_round_trip_lossy_fields() {
+        let rules = crate::parse_rules(SAMPLE_RULES).unwrap();
+        let original = crate::parse_input_json(SAMPLE_AGENT).unwrap();
+        let outcome = crate::convert(&original, rules.as_ref(), None);
+        let mut exported = export_to_agentforce(&outcome.nga, rules.as_ref());
+
+        // Match exported plugins to the original ones; default topics added by the converter are extra
+        let original_plugins = original.plugins.as_ref().unwrap();
+        let exported_plugins = exported.plugins.take().unwrap();
+        let (matched, extra): (Vec<_>, Vec<_>) = exported_plugins
+            .into_iter()
+            .partition(|e| original_plugins.iter().any(|p| plugin_key(p) == plugin_key(e)));
+        let mut extra_names: Vec<_> = extra.iter().map(|p| p.name.as_str()).collect();
+        extra_names.sort();
+        assert_eq!(extra_names, ["ambiguous_question", "off_topic"]);
+
+        exported.plugins = Some(
+            original_plugins
+                .iter()
+                .filter_map(|p| matched.iter().find(|e| plugin_key(e) == plugin_key(p)).cloned())
+                .collect(),
+        );
+
+        let mut differences = BTreeSet::new();
+        collect_differences(
+            &serde_json::to_value(&original).unwrap(),
+            &serde_json::to_value(&exported).unwrap(),
+            "",
+            &mut differences,
+        );
+
+        let expected: BTreeSet<String> = KNOWN_LOSSY_FIELDS.iter().map(|s| s.to_string()).collect();
+        assert_eq!(differences, expected);
+    }
+
+    #[test]
+    fn test_export_action_schema() {
+        let mut inputs = IndexMap::new();
+        inputs.insert("caseRecord".to_string(), ActionInputDef {
+            input_type: "object".to_string(),
+            const_value: None,
+            description: Some("The case".to_string()),
+            label: Some("Case record".to_string()),
+            is_required: true,
+            is_user_input: true,
+            complex_data_type_name: Some("lightning__recordInfoType".to_string()),
+        });
+        let mut outputs = IndexMap::new();
+        outputs.insert("cases".to_string(), ActionOutputDef {
+            output_type: "list[object]".to_string(),
+            description: None,
+            label: None,
+            is_displayable: true,
+            is_used_by_planner: false,
+            complex_data_type_name: None,
+        });
+        let action = Action {
+            description: "Update a case".to_string(),
+            label: None,
+            require_user_confirmation: true,
+            include_in_progress_indicator: false,
+            progress_indicator_message: None,
+            source: None,
+            target: "flow://Update_Case".to_string(),
+            inputs: Some(inputs),
+            outputs: Some(outputs),
+        };
+
+        let function = export_action("update_case", &action, None);
+
+        assert_eq!(function.invocation_target_type.as_deref(), Some("flow"));
+        assert_eq!(function.invocation_target_name.as_deref(), Some("Update_Case"));
+
+        let input_type = function.input_type.unwrap();
+        assert_eq!(input_type.required, Some(vec!["caseRecord".to_string()]));
+        let case_record = &input_type.properties.unwrap()["caseRecord"];
+        assert_eq!(case_record.prop_type.as_deref(), Some("object"));
+        assert_eq!(case_record.lightning_type.as_deref(), Some("lightning__recordInfoType"));
+        assert_eq!(case_record.ref_type.as_deref(), Some("#/$defs/lightning__recordInfoType"));
+        assert_eq!(case_record.title.as_deref(), Some("Case record"));
+
+        let cases = &function.output_type.unwrap().properties.unwrap()["cases"];
+        assert_eq!(cases.prop_type.as_deref(), Some("array"));
+        assert_eq!(cases.items.as_ref().unwrap().prop_type.as_deref(), Some("object"));
+        assert_eq!(cases.is_displayable, Some(true));
+    }
+
+    #[test]
+    fn test_export_topic_plugin() {
+        let outcome = crate::convert_json(SAMPLE_AGENT, None, None).unwrap();
+        let exported = export_to_agentforce(&outcome.nga, None);
+
+        let plugins = exported.plugins.unwrap();
+        assert!(plugins.iter().all(|p| p.plugin_type.as_deref() == Some("TOPIC")));
+        assert!(!plugins.iter().any(|p| p.name == "topic_selector"));
+
+        assert_eq!(exported.id.as_deref(), Some("16jKc0000004Cqw"));
+        assert_eq!(exported.secondary_locales, Some(vec!["es".to_string(), "es_MX".to_string()]));
+    }
+
+    #[test]
+    fn test_export_planner_configuration_by_value_kind() {
+        let by_name = export_planner_configuration("AiRagFeaturePromptContent_1cc3acca".to_string());
+        assert_eq!(by_name.rag_feature_name.as_deref(), Some("AiRagFeaturePromptContent_1cc3acca"));
+        assert_eq!(by_name.rag_feature_id, None);
+
+        let by_id = export_planner_configuration("1JeKc00000000G4KAI".to_string());
+        assert_eq!(by_id.rag_feature_id.as_deref(), Some("1JeKc00000000G4KAI"));
+        assert_eq!(by_id.rag_feature_name, None);
+    }
+
+    #[test]
+    fn test_export_escalation_from_script() {
+        let nga = crate::parse_agent_script(include_str!("../../website/nga_sample.yaml"), None).unwrap();
+        let exported = export_to_agentforce(&nga, None);
+
+        let plugins = exported.plugins.unwrap();
+        let escalation = plugins.iter().find(|p| p.name == "escalation").unwrap();
+        assert_eq!(escalation.can_escalate, Some(true));
+        assert_eq!(escalation.instruction_definitions.as_ref().map(|d| d.len()), Some(2));
+        let off_topic = plugins.iter().find(|p| p.name == "off_topic").unwrap();
+        assert_eq!(off_topic.can_escalate, Some(false));
+    }
+
+    #[test]
+    fn test_export_target_with_rules_syntax() {
+        let rules = crate::parse_rules(r#"{"target_format": {
+            "syntax": "{invocation_type}:{target_name}",
+            "mappings": {"standardInvocableAction": "standard", "flow": "flow"}
+        }}"#).unwrap();
+        assert_eq!(
+            split_action_target("standard:streamKnowledgeSearch", rules.as_ref()),
+            Some(("standardInvocableAction".to_string(), "streamKnowledgeSearch".to_string()))
+        );
+        assert_eq!(split_action_target("custom:Thing", rules.as_ref()), Some(("custom".to_string(), "Thing".to_string())));
+        assert_eq!(split_action_target("flow://Get_Order", None), Some(("flow".to_string(), "Get_Order".to_string())));
+        assert_eq!(split_action_target("Get_Order", None), None);
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashSet;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::salesforce_id::is_salesforce_id;
+
 // ============================================================================
 // CONSTANTS
 // ============================================================================
@@ -26,6 +30,66 @@ pub const YAML_TRUE: &str = "True";
 /// YAML boolean string for false  
 pub const YAML_FALSE: &str = "False";
 
+/// Value of a `with` clause the planner fills from the conversation
+pub const SLOT_FILL: &str = "...";
+
+/// Prefix of variable references in Agent Script
+pub const VARIABLE_REFERENCE_PREFIX: &str = "@variables.";
+
+/// Prefix of action output references in Agent Script
+pub const OUTPUT_REFERENCE_PREFIX: &str = "@outputs.";
+
+/// Reasoning action target used by the escalation utility
+pub const ESCALATE_TARGET: &str = "@utils.escalate";
+
+/// Name of the reasoning action escalating to a human agent
+pub const ESCALATION_ACTION_NAME: &str = "escalate_to_human";
+
+/// Description of the reasoning action escalating to a human agent
+pub const ESCALATION_ACTION_DESCRIPTION: &str = "Call this tool to escalate to a human agent.";
+
+/// Prefix of transition targets in reasoning actions
+pub const TRANSITION_PREFIX: &str = "@utils.transition to @topic.";
+
+/// Default description of topic selector transitions
+pub const DEFAULT_TRANSITION_DESCRIPTION: &str = "{description} {classification}";
+
+/// Start of classification descriptions built from example utterances
+pub const CLASSIFICATION_EXAMPLES_PREFIX: &str = "Route here for requests like:";
+
+/// Start of comments the converter writes for constructs it could not translate
+pub const UNSUPPORTED_COMMENT_PREFIX: &str = "Unsupported";
+
+/// Default syntax of action targets
+pub const DEFAULT_TARGET_SYNTAX: &str = "{invocation_type}://{target_name}";
+
+/// Invocation type of functions and actions that do not set one
+pub const DEFAULT_TARGET_TYPE: &str = "action";
+
+/// Invocation types mapped to themselves when the rules have no `target_format.mappings`
+pub const DEFAULT_TARGET_TYPES: &[&str] = &["flow", "apex", "standardInvocableAction", "generatePromptResponse", "action"];
+
+/// Name transforms accepted in `target_format.name_transforms` (the first keeps the name)
+pub const TARGET_NAME_TRANSFORMS: &[&str] = &["as_is", "lowercase", "snake_case", "strip_namespace"];
+
+/// Key prefixes of the record IDs Salesforce appends to plugin (`16j`) and
+/// function (`179`) names, e.g. `General_Web_Search_16jKc0000004Cqw`
+pub const RECORD_ID_SUFFIX_PREFIXES: &[&str] = &["16j", "179"];
+
+/// Default syntax of post-action assignments (`{variable}` and `{value}` are placeholders)
+pub const DEFAULT_ASSIGNMENT_SYNTAX: &str = "set {variable} = {value}";
+
+/// Default number of spaces per indentation level
+pub const DEFAULT_INDENTATION: usize = 4;
+
+/// Default order of top-level Agent Script blocks
+pub const DEFAULT_BLOCK_ORDER: &[&str] = &[
+    "system", "config", "variables", "language", "knowledge", "connection", "start_agent", "topic",
+];
+
+/// Blocks required when the rules do not list any
+pub const DEFAULT_REQUIRED_BLOCKS: &[&str] = &["system", "config", "start_agent"];
+
 // ============================================================================
 // STATIC REGEX PATTERNS (compiled once at startup)
 // ============================================================================
@@ -35,40 +99,73 @@ static MARKDOWN_TAG_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"#[A-Za-z]+#").expect("Invalid regex pattern for MARKDOWN_TAG_RE")
 });
 
-/// Sanitize topic name to valid format
-pub fn sanitize_topic_name(name: Option<&str>) -> String {
-    let name = name.unwrap_or("unnamed");
+/// Check if a name segment is a record ID Salesforce appends to plugin or function names
+fn is_record_id_suffix(segment: &str) -> bool {
+    is_salesforce_id(segment) && RECORD_ID_SUFFIX_PREFIXES.iter().any(|prefix| segment.starts_with(prefix))
+}
+
+/// Remove a trailing record ID segment from a name
+/// (`General_Web_Search_16jKc0000004Cqw` -> `General_Web_Search`); a name that
+/// is only such an ID becomes empty
+pub fn strip_record_id_suffix(name: &str) -> &str {
+    match name.rsplit_once(|c: char| !c.is_alphanumeric()) {
+        Some((head, last)) if is_record_id_suffix(last) => head.trim_end_matches(|c: char| !c.is_alphanumeric()),
+        None if is_record_id_suffix(name) => "",
+        _ => name,
+    }
+}
+
+/// Split a name into underscore-separated segments of alphanumerics,
+/// dropping a trailing record ID segment
+fn name_segments(name: &str) -> Vec<String> {
     let cleaned: String = name
         .chars()
         .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
         .collect();
     
-    cleaned
+    let mut segments: Vec<String> = cleaned
         .split('_')
         .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("_")
-        .trim_start_matches('_')
-        .trim_end_matches('_')
-        .to_lowercase()
+        .map(str::to_string)
+        .collect();
+    if segments.last().is_some_and(|last| is_record_id_suffix(last)) {
+        segments.pop();
+    }
+    segments
+}
+
+/// Sanitize topic name to valid format
+/// Trailing record ID segments are removed
+pub fn sanitize_topic_name(name: Option<&str>) -> String {
+    name_segments(name.unwrap_or("unnamed")).join("_").to_lowercase()
 }
 
 /// Sanitize action name to valid format
+/// Trailing record ID segments are removed
 pub fn sanitize_action_name(name: Option<&str>) -> String {
-    let name = name.unwrap_or("action");
-    let cleaned: String = name
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
-        .collect();
+    name_segments(name.unwrap_or("action")).join("_")
+}
+
+/// Make names unique by suffixing repeats with `_2`, `_3`, ... in order
+/// The first occurrence keeps its name, and suffixes skip names already in the list
+pub fn unique_names(names: &[String]) -> Vec<String> {
+    let mut taken: HashSet<String> = names.iter().cloned().collect();
+    let mut seen = HashSet::new();
     
-    cleaned
-        .split('_')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("_")
-        .trim_start_matches('_')
-        .trim_end_matches('_')
-        .to_string()
+    names
+        .iter()
+        .map(|name| {
+            if seen.insert(name.as_str()) {
+                return name.clone();
+            }
+            let unique = (2..)
+                .map(|n| format!("{}_{}", name, n))
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or_else(|| name.clone());
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
 }
 
 /// Generate developer name from label/name
@@ -152,11 +249,6 @@ pub fn get_default_language_values() -> (String, bool) {
     (DEFAULT_LOCALE.to_string(), DEFAULT_ALL_ADDITIONAL_LOCALES)
 }
 
-/// Format boolean value for YAML output
-pub fn format_boolean_value(value: bool) -> String {
-    if value { YAML_TRUE } else { YAML_FALSE }.to_string()
-}
-
 /// Merge description and scope into single description
 pub fn merge_description_and_scope(
     description: Option<&str>,
@@ -216,6 +308,31 @@ mod tests {
         assert_eq!(sanitize_action_name(None), "action");
     }
 
+    #[test]
+    fn test_unique_names() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(unique_names(&names(&["a", "b", "a", "a"])), names(&["a", "b", "a_2", "a_3"]));
+        // Suffixes skip names that are already taken
+        assert_eq!(unique_names(&names(&["a", "a", "a_2"])), names(&["a", "a_3", "a_2"]));
+        assert_eq!(unique_names(&names(&["a_2", "a", "a"])), names(&["a_2", "a", "a_3"]));
+    }
+
+    #[test]
+    fn test_sanitizers_strip_record_id_suffixes() {
+        assert_eq!(sanitize_topic_name(Some("General_Web_Search_16jKc0000004Cqw")), "general_web_search");
+        assert_eq!(sanitize_topic_name(Some("Escalation_16jKc0000004CqwIAE")), "escalation");
+        assert_eq!(sanitize_action_name(Some("AnswerQuestionsWithKnowledge_179Kc0000004HuX")), "AnswerQuestionsWithKnowledge");
+        // Only a trailing ID with a plugin/function key prefix is removed
+        assert_eq!(sanitize_topic_name(Some("Orders_001xx000003DGbY")), "orders_001xx000003dgby");
+        assert_eq!(sanitize_topic_name(Some("16jKc0000004Cqw_Orders")), "16jkc0000004cqw_orders");
+        assert_eq!(sanitize_topic_name(Some("16jKc0000004Cqw")), "");
+
+        assert_eq!(strip_record_id_suffix("General_Web_Search_16jKc0000004Cqw"), "General_Web_Search");
+        assert_eq!(strip_record_id_suffix("16jKc0000004Cqw"), "");
+        assert_eq!(strip_record_id_suffix("CaseManagement"), "CaseManagement");
+        assert_eq!(strip_record_id_suffix("Orders_ééééééééé"), "Orders_ééééééééé");
+    }
+
     #[test]
     fn test_generate_developer_name() {
         assert_eq!(generate_developer_name("My Agent"), "MY_AGENT");
@@ -279,12 +396,6 @@ mod tests {
         assert_eq!(all_locales, DEFAULT_ALL_ADDITIONAL_LOCALES);
     }
 
-    #[test]
-    fn test_format_boolean_value() {
-        assert_eq!(format_boolean_value(true), YAML_TRUE);
-        assert_eq!(format_boolean_value(false), YAML_FALSE);
-    }
-
     #[test]
     fn test_merge_description_and_scope() {
         assert_eq!(
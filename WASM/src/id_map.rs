@@ -0,0 +1,294 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::{ConvertError, ErrorKind};
+use crate::models::*;
+use crate::salesforce_id::to_18;
+
+// ============================================================================
+// RECORD ID RESOLUTION MAP
+// ============================================================================
+
+/// Table resolving Salesforce record IDs to API names
+///
+/// Exports reference flows and Apex classes by record ID when the API name is
+/// not available (e.g. `flow://300xx0000001AbCAAU`). The table is produced
+/// once per org (from a CSV export or a JSON map of a query result) and used
+/// to rewrite those targets before conversion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdMap {
+    entries: HashMap<String, String>,
+}
+
+impl IdMap {
+    /// Parse a resolution table
+    ///
+    /// Accepted formats:
+    /// - JSON object: `{ "300xx0000001AbCAAU": "Get_Order" }`
+    /// - JSON array of query records: `[{ "Id": "300...", "ApiName": "Get_Order" }]`
+    ///   (`DeveloperName` or `Name` are used when `ApiName` is absent)
+    /// - CSV with `id,api_name` columns; a header row, blank lines and `#`
+    ///   comment lines are skipped
+    pub fn parse(text: &str) -> Result<IdMap, ConvertError> {
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            Self::parse_json(text)
+        } else {
+            Self::parse_csv(text)
+        }
+    }
+
+    /// Parse a JSON object or an array of query records
+    fn parse_json(text: &str) -> Result<IdMap, ConvertError> {
+        let value: Value = serde_json::from_str(text).map_err(|e| {
+            ConvertError::new(ErrorKind::InvalidInput, "", format!("Failed to parse ID map JSON: {}", e))
+        })?;
+
+        let mut map = IdMap::default();
+        match value {
+            Value::Object(object) => {
+                for (id, api_name) in object {
+                    let api_name = api_name.as_str().ok_or_else(|| {
+                        ConvertError::new(ErrorKind::InvalidInput, id.clone(), "ID map values must be API name strings")
+                    })?;
+                    map.insert(&id, api_name);
+                }
+            }
+            Value::Array(records) => {
+                for (i, record) in records.iter().enumerate() {
+                    let id = record.get("Id").and_then(Value::as_str);
+                    let api_name = ["ApiName", "DeveloperName", "Name"]
+                        .iter()
+                        .find_map(|key| record.get(*key).and_then(Value::as_str));
+                    match (id, api_name) {
+                        (Some(id), Some(api_name)) => map.insert(id, api_name),
+                        _ => {
+                            return Err(ConvertError::new(
+                                ErrorKind::InvalidInput,
+                                format!("[{}]", i),
+                                "ID map records need an Id and an ApiName, DeveloperName or Name",
+                            ))
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(ConvertError::new(
+                    ErrorKind::InvalidInput,
+                    "",
+                    "ID map JSON must be an object or an array of records",
+                ))
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Parse `id,api_name` CSV lines
+    fn parse_csv(text: &str) -> Result<IdMap, ConvertError> {
+        let mut map = IdMap::default();
+        let mut first_row = true;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut columns = line.split(',').map(|c| c.trim().trim_matches('"').trim());
+            let (Some(id), Some(api_name)) = (columns.next(), columns.next()) else {
+                return Err(ConvertError::new(
+                    ErrorKind::InvalidInput,
+                    format!("line {}", index + 1),
+                    "ID map CSV lines need an id and an api_name column",
+                ));
+            };
+
+            // Header row
+            if std::mem::take(&mut first_row) && id.eq_ignore_ascii_case("id") {
+                continue;
+            }
+            map.insert(id, api_name);
+        }
+
+        Ok(map)
+    }
+
+    /// Add an entry, ignoring empty IDs or names
+    /// Record IDs are stored in their 18-character form
+    pub fn insert(&mut self, id: &str, api_name: &str) {
+        if !id.is_empty() && !api_name.is_empty() {
+            let id = to_18(id).unwrap_or_else(|| id.to_string());
+            self.entries.insert(id, api_name.to_string());
+        }
+    }
+
+    /// Number of entries in the table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the table has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up the API name for a record ID
+    /// 15- and 18-character forms of the same ID match each other
+    pub fn resolve(&self, id: &str) -> Option<&str> {
+        let id = to_18(id).unwrap_or_else(|| id.to_string());
+        self.entries.get(&id).map(String::as_str)
+    }
+
+    /// Resolve a `scheme://ID` target or a bare ID, keeping the scheme
+    fn resolve_target(&self, target: &str) -> Option<String> {
+        match target.split_once("://") {
+            Some((scheme, id)) => self.resolve(id).map(|name| format!("{}://{}", scheme, name)),
+            None => self.resolve(target).map(str::to_string),
+        }
+    }
+}
+
+// ============================================================================
+// INPUT REWRITING
+// ============================================================================
+
+/// Replace a field holding a record ID with its API name
+/// Returns true if the field was rewritten
+fn rewrite(field: &mut Option<String>, map: &IdMap) -> bool {
+    let Some(resolved) = field.as_deref().and_then(|value| map.resolve_target(value)) else {
+        return false;
+    };
+    *field = Some(resolved);
+    true
+}
+
+/// Rewrite record-ID action targets and sources in an agent definition
+///
+/// Functions whose `invocationTargetName` is missing get the name resolved from
+/// `invocationTargetId`. IDs missing from the table are left untouched so the
+/// report still lists them for manual review.
+/// Returns the number of rewritten values.
+pub fn apply_id_map(input: &mut AgentforceInput, map: &IdMap) -> usize {
+    let mut rewritten = 0;
+
+    for plugin in input.plugins.iter_mut().flatten() {
+        for function in plugin.functions.iter_mut().flatten() {
+            if function.invocation_target_name.is_some() {
+                rewritten += usize::from(rewrite(&mut function.invocation_target_name, map));
+            } else if let Some(name) = function.invocation_target_id.as_deref().and_then(|id| map.resolve(id)) {
+                function.invocation_target_name = Some(name.to_string());
+                rewritten += 1;
+            }
+            rewritten += usize::from(rewrite(&mut function.source, map));
+        }
+    }
+
+    for topic in input.topics.iter_mut().flatten() {
+        for action in topic.actions.iter_mut().flatten() {
+            for field in [&mut action.target, &mut action.invocation_target, &mut action.target_name, &mut action.source] {
+                rewritten += usize::from(rewrite(field, map));
+            }
+        }
+    }
+
+    rewritten
+}
+
+/// Apply an optional ID map to an agent definition
+/// The input is only copied when the map rewrites something
+pub fn resolve_input<'a>(input: &'a AgentforceInput, map: Option<&IdMap>) -> Cow<'a, AgentforceInput> {
+    let Some(map) = map.filter(|map| !map.is_empty()) else {
+        return Cow::Borrowed(input);
+    };
+    let mut resolved = input.clone();
+    if apply_id_map(&mut resolved, map) == 0 {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(resolved)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_and_json_formats() {
+        let csv = IdMap::parse("# exported 2024-05-01\nId,ApiName\n300xx0000001AbC,Get_Order\n\n\"01pxx0000002XyZ\",\"OrderService\"\n").unwrap();
+        assert_eq!(csv.len(), 2);
+        assert_eq!(csv.resolve("01pxx0000002XyZ"), Some("OrderService"));
+
+        let object = IdMap::parse(r#"{ "300xx0000001AbC": "Get_Order" }"#).unwrap();
+        let records = IdMap::parse(r#"[{ "Id": "300xx0000001AbC", "DeveloperName": "Get_Order" }]"#).unwrap();
+        assert_eq!(object, records);
+
+        // 15- and 18-character forms of an ID match each other
+        assert_eq!(object.resolve("300xx0000001AbCAAU"), Some("Get_Order"));
+        assert_eq!(IdMap::parse("300xx0000001AbCAAU,Get_Order").unwrap().resolve("300xx0000001AbC"), Some("Get_Order"));
+        assert_eq!(object.resolve("300xx0000009ZzZAAU"), None);
+
+        assert!(IdMap::parse("just-one-column").is_err());
+        assert!(IdMap::parse(r#"{ "300xx0000001AbC": 1 }"#).is_err());
+    }
+
+    #[test]
+    fn test_multibyte_key_is_matched_verbatim() {
+        // 18 bytes but not an ID, so it is not normalized to an 18-character form
+        let map = IdMap::parse("ééééééééé,Get_Order").unwrap();
+        assert_eq!(map.resolve("ééééééééé"), Some("Get_Order"));
+        assert_eq!(map.resolve("éééééééé"), None);
+    }
+
+    #[test]
+    fn test_apply_id_map_rewrites_targets_and_leaves_unresolved_ids() {
+        let mut input = crate::parse_input_json(r#"{
+            "plugins": [{
+                "name": "Orders",
+                "functions": [
+                    { "name": "GetOrder", "invocationTargetType": "flow", "invocationTargetName": "300xx0000001AbCAAU" },
+                    { "name": "Refund", "invocationTargetType": "apex", "invocationTargetId": "01pxx0000002XyZ", "source": "01pxx0000002XyZ" },
+                    { "name": "Cancel", "invocationTargetType": "flow", "invocationTargetName": "300xx0000009ZzZAAU" }
+                ]
+            }],
+            "topics": [{
+                "name": "Billing",
+                "actions": [{ "name": "Pay", "invocation_target": "flow://300xx0000001AbCAAU" }]
+            }]
+        }"#).unwrap();
+        let map = IdMap::parse("300xx0000001AbC,Get_Order\n01pxx0000002XyZ,OrderService").unwrap();
+
+        assert_eq!(apply_id_map(&mut input, &map), 4);
+
+        let functions = input.plugins.as_ref().unwrap()[0].functions.as_ref().unwrap();
+        assert_eq!(functions[0].invocation_target_name.as_deref(), Some("Get_Order"));
+        assert_eq!(functions[1].invocation_target_name.as_deref(), Some("OrderService"));
+        assert_eq!(functions[1].source.as_deref(), Some("OrderService"));
+        assert_eq!(functions[2].invocation_target_name.as_deref(), Some("300xx0000009ZzZAAU"));
+
+        let action = &input.topics.as_ref().unwrap()[0].actions.as_ref().unwrap()[0];
+        assert_eq!(action.invocation_target.as_deref(), Some("flow://Get_Order"));
+    }
+
+    #[test]
+    fn test_resolve_input_copies_only_when_rewritten() {
+        let input = crate::parse_input_json(r#"{
+            "plugins": [{ "name": "Orders", "functions": [{ "name": "GetOrder", "invocationTargetName": "300xx0000001AbCAAU" }] }]
+        }"#).unwrap();
+
+        assert!(matches!(resolve_input(&input, None), Cow::Borrowed(_)));
+        let unrelated = IdMap::parse("01pxx0000002XyZ,OrderService").unwrap();
+        assert!(matches!(resolve_input(&input, Some(&unrelated)), Cow::Borrowed(_)));
+
+        let map = IdMap::parse("300xx0000001AbC,Get_Order").unwrap();
+        let resolved = resolve_input(&input, Some(&map));
+        assert!(matches!(resolved, Cow::Owned(_)));
+        let function = &resolved.plugins.as_ref().unwrap()[0].functions.as_ref().unwrap()[0];
+        assert_eq!(function.invocation_target_name.as_deref(), Some("Get_Order"));
+    }
+}
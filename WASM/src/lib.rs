@@ -1,193 +1,404 @@
-//! NGA YAML Interpreter - Core conversion logic compiled to WebAssembly
-//! 
+//! NGA YAML Interpreter - Core conversion logic
+//!
 //! This crate provides the conversion logic to transform Salesforce Agentforce
 //! JSON/YAML into the Next Generation Agent (NGA) script format.
+//!
+//! The functions at the crate root ([`convert`], [`parse_input_json`],
+//! [`parse_rules`], ...) are plain, target-independent Rust. The WebAssembly
+//! bindings used by the website are a thin layer behind the `wasm` feature.
 
 #![warn(clippy::all)]
 #![allow(clippy::module_name_repetitions)]
 #![allow(clippy::too_many_lines)]
 
-mod models;
-mod helpers;
-mod variable_processor;
-mod converter;
-mod yaml_generator;
-mod report_generator;
+pub mod models;
+pub mod helpers;
+pub mod variable_processor;
+pub mod converter;
+pub mod rule_translator;
+pub mod yaml_generator;
+pub mod script_writer;
+pub mod report_generator;
+pub mod script_parser;
+pub mod exporter;
+pub mod id_map;
+pub mod salesforce_id;
+pub mod validator;
+pub mod error;
+
+#[cfg(feature = "wasm")]
+mod wasm;
 
-use wasm_bindgen::prelude::*;
 use crate::models::*;
 use crate::converter::*;
 use crate::yaml_generator::*;
 use crate::variable_processor::*;
-use crate::report_generator::*;
+use crate::report_generator::{ReportData, ReportMetadata};
+
+pub use crate::error::{ConvertError, ErrorKind, ScriptError};
+pub use crate::validator::{Diagnostic, Severity};
+pub use crate::id_map::{apply_id_map, resolve_input, IdMap};
 
 // ============================================================================
-// INITIALIZATION
+// PUBLIC API
 // ============================================================================
 
-/// Initialize WASM module with panic hook for better error messages
-#[wasm_bindgen(start)]
-pub fn init() {
-    #[cfg(feature = "console_error_panic_hook")]
-    console_error_panic_hook::set_once();
+/// Result of a successful conversion
+#[derive(Debug, Clone)]
+pub struct ConversionOutcome {
+    /// The converted agent in NGA model form
+    pub nga: NGAOutput,
+    /// The generated NGA Agent Script text
+    pub yaml: String,
+    /// Whether the input contained variables that were converted to @variables format
+    pub has_variables_with_dollar: bool,
+    /// Number of topics in the output
+    pub topic_count: usize,
+    /// Number of actions in the output
+    pub action_count: usize,
+    /// Alert message for variable conversion (empty when no variables were converted)
+    pub alert_message: String,
+    /// Status suffix for variable conversion (empty when no variables were converted)
+    pub status_suffix: String,
+    /// Cross-reference findings in the output (dangling transitions or action
+    /// references, unreachable topics); the conversion itself succeeded
+    pub warnings: Vec<Diagnostic>,
+    /// Topic, action and parameter names that were empty after sanitization (replaced
+    /// by the label or a placeholder) or suffixed (`_2`, `_3`, ...) because they
+    /// collided with an earlier name
+    pub renames: Vec<Rename>,
 }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+/// Parse an agent definition from JSON text
+pub fn parse_input_json(input_json: &str) -> Result<AgentforceInput, ConvertError> {
+    let mut deserializer = serde_json::Deserializer::from_str(input_json);
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        ConvertError::from_path_error(ErrorKind::InvalidInput, "Failed to parse input JSON", e)
+    })
+}
+
+/// Parse an agent definition from YAML text
+pub fn parse_input_yaml(input_yaml: &str) -> Result<AgentforceInput, ConvertError> {
+    let deserializer = serde_yaml::Deserializer::from_str(input_yaml);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        ConvertError::from_path_error(ErrorKind::InvalidInput, "Failed to parse input YAML", e)
+    })
+}
 
 /// Parse rules JSON string into ConversionRules
-/// Returns None if the string is empty or parsing fails
-/// Logs a warning to console if parsing fails (for debugging)
-fn parse_rules(rules_json: &str) -> Option<ConversionRules> {
-    if rules_json.is_empty() {
-        return None;
-    }
-    
-    match serde_json::from_str(rules_json) {
-        Ok(rules) => Some(rules),
-        Err(e) => {
-            // Log warning for debugging (don't fail, just use defaults)
-            web_sys::console::warn_1(&format!("Failed to parse rules JSON: {}. Using defaults.", e).into());
-            None
-        }
+/// Returns Ok(None) if the string is empty
+pub fn parse_rules(rules_json: &str) -> Result<Option<ConversionRules>, ConvertError> {
+    if rules_json.trim().is_empty() {
+        return Ok(None);
     }
+
+    let mut deserializer = serde_json::Deserializer::from_str(rules_json);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map(Some)
+        .map_err(|e| {
+            ConvertError::from_path_error(ErrorKind::InvalidRules, "Failed to parse rules JSON", e)
+        })
+}
+
+/// Parse a record ID to API name resolution table (JSON map, JSON records or CSV)
+pub fn parse_id_map(text: &str) -> Result<IdMap, ConvertError> {
+    IdMap::parse(text)
 }
 
-/// Main conversion function - converts input JSON to NGA YAML
-/// 
-/// # Arguments
-/// * `input_json` - JSON string of the input agent configuration
-/// * `rules_json` - Optional JSON string of conversion rules (can be empty string)
-/// 
-/// # Returns
-/// JSON object with:
-/// - `yaml`: The converted YAML string
-/// - `has_variables_with_dollar`: Boolean indicating if variables were converted
-/// - `topic_count`: Number of topics
-/// - `action_count`: Number of actions
-#[wasm_bindgen]
-pub fn convert_agent(input_json: &str, rules_json: &str) -> Result<JsValue, JsValue> {
-    // Parse input JSON
-    let input: AgentforceInput = serde_json::from_str(input_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse input JSON: {}", e)))?;
-    
-    // Parse rules JSON using helper function
-    let rules = parse_rules(rules_json);
-    
+/// Convert an agent definition to NGA Agent Script
+///
+/// Record IDs found in `id_map` are rewritten to API names before conversion.
+pub fn convert(
+    input: &AgentforceInput,
+    rules: Option<&ConversionRules>,
+    id_map: Option<&IdMap>,
+) -> ConversionOutcome {
+    let input = &*resolve_input(input, id_map);
+
     // Check for variables with $ in the input
-    let input_str = input_json;
-    let has_variables_with_dollar = check_for_dollar_variables(input_str, &rules);
-    
+    let has_variables_with_dollar = has_dollar_variables(input, rules);
+
     // Detect format and convert
-    let nga_output = detect_and_convert(&input, &rules)
-        .map_err(|e| JsValue::from_str(&format!("Conversion error: {}", e)))?;
-    
+    let (nga, renames) = detect_and_convert(input, rules);
+
     // Generate YAML
-    let yaml_output = generate_nga_yaml(&nga_output, &rules);
-    
-    // Count topics and actions
-    let topic_count = nga_output.topics.len();
-    let action_count = nga_output
-        .topics
-        .values()
-        .map(|topic| {
-            topic.actions.as_ref().map(|a| a.len()).unwrap_or(0)
-        })
-        .sum::<usize>();
-    
-    // Create result object
-    let result = serde_json::json!({
-        "yaml": yaml_output,
-        "has_variables_with_dollar": has_variables_with_dollar,
-        "topic_count": topic_count,
-        "action_count": action_count,
-        "alert_message": if has_variables_with_dollar {
-            get_variable_alert_message(&rules)
-        } else {
-            String::new()
-        },
-        "status_suffix": if has_variables_with_dollar {
-            get_variable_status_suffix(&rules)
-        } else {
-            String::new()
-        }
-    });
-    
-    // Convert to JsValue
-    serde_wasm_bindgen::to_value(&result)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+    let yaml = generate_nga_yaml(&nga, rules);
+
+    let (alert_message, status_suffix) = if has_variables_with_dollar {
+        (get_variable_alert_message(rules), get_variable_status_suffix(rules))
+    } else {
+        (String::new(), String::new())
+    };
+
+    ConversionOutcome {
+        topic_count: nga.topics.len(),
+        action_count: count_nga_actions(&nga),
+        warnings: [converter::check_target_mappings(input, rules), validator::check_references(&nga)].concat(),
+        renames,
+        nga,
+        yaml,
+        has_variables_with_dollar,
+        alert_message,
+        status_suffix,
+    }
+}
+
+/// Parse and convert an agent definition given as JSON text
+pub fn convert_json(
+    input_json: &str,
+    rules: Option<&ConversionRules>,
+    id_map: Option<&IdMap>,
+) -> Result<ConversionOutcome, ConvertError> {
+    Ok(convert(&parse_input_json(input_json)?, rules, id_map))
 }
 
-/// Check if input contains variables with $ sign
-#[wasm_bindgen]
-pub fn check_dollar_variables(input: &str, rules_json: &str) -> bool {
-    let rules = parse_rules(rules_json);
-    check_for_dollar_variables(input, &rules)
+/// Parse and convert an agent definition given as YAML text
+pub fn convert_yaml(
+    input_yaml: &str,
+    rules: Option<&ConversionRules>,
+    id_map: Option<&IdMap>,
+) -> Result<ConversionOutcome, ConvertError> {
+    Ok(convert(&parse_input_yaml(input_yaml)?, rules, id_map))
 }
 
-/// Get variable alert message
-#[wasm_bindgen]
-pub fn get_alert_message(rules_json: &str) -> String {
-    let rules = parse_rules(rules_json);
-    get_variable_alert_message(&rules)
+/// Generate conversion report data for a converted agent
+/// Pass the ID map used for the conversion so resolved targets are not listed for review
+pub fn generate_report(
+    input: &AgentforceInput,
+    output_yaml: &str,
+    metadata: &ReportMetadata,
+    id_map: Option<&IdMap>,
+) -> ReportData {
+    report_generator::generate_report_data(&resolve_input(input, id_map), output_yaml, metadata)
 }
 
-/// Get variable status suffix
-#[wasm_bindgen]
-pub fn get_status_suffix(rules_json: &str) -> String {
-    let rules = parse_rules(rules_json);
-    get_variable_status_suffix(&rules)
+/// Parse NGA Agent Script text back into its NGAOutput model
+pub fn parse_agent_script(
+    script: &str,
+    rules: Option<&ConversionRules>,
+) -> Result<NGAOutput, ScriptError> {
+    script_parser::parse_agent_script(script, rules)
 }
 
-/// Count topics in NGA output (for testing/debugging)
-#[wasm_bindgen]
-pub fn count_topics(nga_json: &str) -> Result<usize, JsValue> {
-    let nga: NGAOutput = serde_json::from_str(nga_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse NGA JSON: {}", e)))?;
-    
-    Ok(nga.topics.len())
+/// Validate an NGA output against the constraints in the conversion rules
+pub fn validate_nga(nga: &NGAOutput, rules: Option<&ConversionRules>) -> Vec<Diagnostic> {
+    validator::validate_nga(nga, rules)
 }
 
-/// Count actions in NGA output (for testing/debugging)
-#[wasm_bindgen]
-pub fn count_actions(nga_json: &str) -> Result<usize, JsValue> {
-    let nga: NGAOutput = serde_json::from_str(nga_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse NGA JSON: {}", e)))?;
-    
-    let count: usize = nga
-        .topics
+/// Export an NGA agent back to the Salesforce Agentforce export shape
+pub fn export_agentforce(nga: &NGAOutput, rules: Option<&ConversionRules>) -> AgentforceInput {
+    exporter::export_to_agentforce(nga, rules)
+}
+
+/// Count the actions defined across all topics of an NGA output
+pub fn count_nga_actions(nga: &NGAOutput) -> usize {
+    nga.topics
         .values()
         .map(|topic| topic.actions.as_ref().map(|a| a.len()).unwrap_or(0))
-        .sum();
-    
-    Ok(count)
+        .sum()
 }
 
-/// Generate conversion report data (IP protected)
-/// 
-/// # Arguments
-/// * `input_json` - JSON string of the input agent configuration
-/// * `output_yaml` - The converted YAML string
-/// * `metadata_json` - JSON string with conversion metadata
-/// 
-/// # Returns
-/// JSON object with structured report data (not markdown)
-#[wasm_bindgen]
-pub fn generate_report_data(input_json: &str, output_yaml: &str, metadata_json: &str) -> Result<JsValue, JsValue> {
-    // Parse input JSON
-    let input: AgentforceInput = serde_json::from_str(input_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse input JSON: {}", e)))?;
-    
-    // Parse metadata JSON
-    let metadata: ReportMetadata = serde_json::from_str(metadata_json)
-        .map_err(|e| JsValue::from_str(&format!("Failed to parse metadata JSON: {}", e)))?;
-    
-    // Generate report data (IP protected logic)
-    let report_data = report_generator::generate_report_data(&input, output_yaml, &metadata)
-        .map_err(|e| JsValue::from_str(&format!("Failed to generate report data: {}", e)))?;
-    
-    // Convert to JsValue
-    serde_wasm_bindgen::to_value(&report_data)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize report data: {}", e)))
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules_empty_is_none() {
+        assert!(parse_rules("").unwrap().is_none());
+        assert!(parse_rules("   ").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_rules_invalid_json_is_error() {
+        let err = parse_rules("{not json").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidRules);
+    }
+
+    #[test]
+    fn test_parse_input_json_invalid_is_error() {
+        let err = parse_input_json("[1, 2]").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        assert!(err.message.starts_with("Failed to parse input JSON:"));
+    }
+
+    #[test]
+    fn test_parse_input_json_reports_failing_path() {
+        let err = parse_input_json(r#"{
+            "plugins": [
+                { "name": "Orders", "functions": [ { "name": "GetOrder", "inputType": "oops" } ] }
+            ]
+        }"#).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        assert_eq!(err.path, "plugins[0].functions[0].inputType");
+    }
+
+    #[test]
+    fn test_dollar_variables_read_from_free_text() {
+        let nested = parse_input_json(r#"{
+            "plugins": [{
+                "name": "Orders",
+                "functions": [{
+                    "name": "GetOrder",
+                    "inputType": { "properties": { "orderId": { "type": "string", "description": "Order of {!$ContactId}" } } }
+                }]
+            }]
+        }"#).unwrap();
+        assert!(convert(&nested, None, None).has_variables_with_dollar);
+
+        // Names and targets are not free text
+        let names_only = parse_input_json(r#"{
+            "plugins": [{ "name": "Orders", "functions": [{ "name": "{$Order}", "invocationTargetName": "{!$Flow}" }] }]
+        }"#).unwrap();
+        assert!(!convert(&names_only, None, None).has_variables_with_dollar);
+    }
+
+    #[test]
+    fn test_convert_resolves_record_ids_from_id_map() {
+        let agent = r#"{
+            "plugins": [{
+                "name": "Orders",
+                "pluginType": "TOPIC",
+                "functions": [{ "name": "GetOrder", "invocationTargetType": "flow", "invocationTargetName": "300xx0000001AbCAAU" }]
+            }]
+        }"#;
+        let map = parse_id_map("300xx0000001AbC,Get_Order").unwrap();
+
+        let resolved = convert_json(agent, None, Some(&map)).unwrap();
+        assert!(resolved.yaml.contains("target: \"flow://Get_Order\""));
+        assert!(!resolved.yaml.contains("300xx0000001AbC"));
+
+        // Same script as converting the rewritten input
+        let mut rewritten = parse_input_json(agent).unwrap();
+        apply_id_map(&mut rewritten, &map);
+        assert_eq!(convert(&rewritten, None, None).yaml, resolved.yaml);
+
+        // Resolved targets are not listed for manual review
+        let metadata: ReportMetadata = serde_json::from_str(
+            r#"{"input_format": "json", "topic_count": 1, "action_count": 1, "has_variables_with_dollar": false}"#
+        ).unwrap();
+        let input = parse_input_json(agent).unwrap();
+        let manual_action = |id_map: Option<&IdMap>| {
+            generate_report(&input, &resolved.yaml, &metadata, id_map)
+                .notes
+                .iter()
+                .any(|note| note.contains("MANUAL ACTION"))
+        };
+        assert!(manual_action(None));
+        assert!(!manual_action(Some(&map)));
+    }
+
+    #[test]
+    fn test_convert_replaces_empty_names() {
+        let input = parse_input_json(r#"{
+            "plugins": [
+                { "name": "Orders", "pluginType": "TOPIC" },
+                {
+                    "name": "___",
+                    "pluginType": "TOPIC",
+                    "functions": [
+                        { "name": "---", "label": "Look Up Order", "inputType": {"properties": {"Input:": {"type": "string"}}} }
+                    ]
+                },
+                { "name": "%%%", "pluginType": "TOPIC" }
+            ]
+        }"#).unwrap();
+
+        let outcome = convert(&input, None, None);
+
+        let topics: Vec<_> = outcome.nga.topics.keys().filter(|key| key.starts_with("topic ")).collect();
+        assert_eq!(topics[..3], ["topic orders", "topic topic", "topic topic_2"]);
+        let actions = outcome.nga.topics["topic topic"].actions.as_ref().unwrap();
+        assert_eq!(actions.keys().collect::<Vec<_>>(), ["Look_Up_Order"]);
+        assert_eq!(actions["Look_Up_Order"].inputs.as_ref().unwrap().keys().collect::<Vec<_>>(), ["input"]);
+
+        let rename = |path: &str, original: &str, name: &str| Rename {
+            path: path.to_string(),
+            original: original.to_string(),
+            name: name.to_string(),
+        };
+        assert_eq!(outcome.renames, [
+            rename("plugins[1].name", "___", "topic"),
+            rename("plugins[2].name", "%%%", "topic_2"),
+            rename("plugins[1].functions[0].name", "---", "Look_Up_Order"),
+            rename("plugins[1].functions[0].inputType.properties.Input:", "Input:", "input"),
+        ]);
+    }
+
+    #[test]
+    fn test_convert_simple_agent() {
+        let input = parse_input_json(r#"{
+            "name": "Support_Agent",
+            "description": "Helps with {!$Topic} questions",
+            "topics": [
+                {
+                    "name": "Orders",
+                    "description": "Order status questions",
+                    "actions": [
+                        { "name": "GetOrder", "description": "Look up an order", "invocation_target": "flow://Get_Order" }
+                    ]
+                }
+            ]
+        }"#).unwrap();
+
+        let outcome = convert(&input, None, None);
+
+        assert!(outcome.yaml.contains("topic orders:"));
+        assert!(outcome.has_variables_with_dollar);
+        assert!(!outcome.alert_message.is_empty());
+        assert_eq!(outcome.topic_count, outcome.nga.topics.len());
+        assert_eq!(outcome.action_count, 1);
+        assert!(outcome.renames.is_empty());
+    }
+
+    #[test]
+    fn test_convert_suffixes_colliding_names() {
+        let input = parse_input_json(r#"{
+            "plugins": [
+                {
+                    "name": "My-Topic",
+                    "pluginType": "TOPIC",
+                    "functions": [
+                        {
+                            "name": "GetOrder",
+                            "inputType": {"properties": {"Input:orderId": {"type": "string"}, "orderId": {"type": "string"}}}
+                        },
+                        {"name": "Get Order"},
+                        {"name": "Get-Order"}
+                    ]
+                },
+                { "name": "My Topic", "pluginType": "TOPIC" },
+                { "name": "my_topic", "localDevName": "my_topic", "pluginType": "TOPIC" }
+            ]
+        }"#).unwrap();
+
+        let outcome = convert(&input, None, None);
+
+        let topic = &outcome.nga.topics["topic my_topic"];
+        assert!(outcome.nga.topics.contains_key("topic my_topic_2"));
+        assert!(outcome.nga.topics.contains_key("topic my_topic_3"));
+        let selector = outcome.nga.topics["start_agent topic_selector"].reasoning.actions.as_ref().unwrap();
+        assert!(selector.contains_key("go_to_my_topic_3"));
+
+        let actions = topic.actions.as_ref().unwrap();
+        assert_eq!(actions.keys().collect::<Vec<_>>(), ["GetOrder", "Get_Order", "Get_Order_2"]);
+        assert!(topic.reasoning.actions.as_ref().unwrap().contains_key("Get_Order_2"));
+        let inputs = actions["GetOrder"].inputs.as_ref().unwrap();
+        assert_eq!(inputs.keys().collect::<Vec<_>>(), ["orderId", "orderId_2"]);
+
+        let rename = |path: &str, original: &str, name: &str| Rename {
+            path: path.to_string(),
+            original: original.to_string(),
+            name: name.to_string(),
+        };
+        assert_eq!(outcome.renames, [
+            rename("plugins[1].name", "My Topic", "my_topic_2"),
+            rename("plugins[2].localDevName", "my_topic", "my_topic_3"),
+            rename("plugins[0].functions[2].name", "Get-Order", "Get_Order_2"),
+            rename("plugins[0].functions[0].inputType.properties.orderId", "orderId", "orderId_2"),
+        ]);
+    }
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use indexmap::IndexMap;
 
 // ============================================================================
 // INPUT MODELS (from JavaScript/JSON)
@@ -21,11 +22,30 @@ pub struct AgentforceInput {
     pub welcome_message_alt: Option<String>,
     pub user_location: Option<String>,
     pub voice_config: Option<serde_json::Value>,
+    pub adl_planner_configurations: Option<Vec<AdlPlannerConfiguration>>,
     pub plugins: Option<Vec<Plugin>>,
     pub topics: Option<Vec<TopicInput>>,
     pub variables: Option<Vec<VariableInput>>,
 }
 
+/// RAG feature configuration of the planner (one per grounding library)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdlPlannerConfiguration {
+    pub rag_feature_id: Option<String>,
+    pub rag_feature_name: Option<String>,
+    pub rag_feature_fully_qualified_name: Option<String>,
+    pub adl_configuration: Option<AdlConfiguration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdlConfiguration {
+    pub ai_ground_library_label: Option<String>,
+    pub ai_grounding_library_id: Option<String>,
+    pub ai_grounding_library_name: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Plugin {
@@ -36,8 +56,34 @@ pub struct Plugin {
     pub scope: Option<String>,
     pub plugin_type: Option<String>,
     pub instruction_definitions: Option<Vec<InstructionDefinition>>,
+    pub utterance_definitions: Option<Vec<UtteranceDefinition>>,
     pub functions: Option<Vec<Function>>,
     pub can_escalate: Option<bool>,
+    pub rule_expressions: Option<Vec<RuleExpression>>,
+    pub workflow_config: Option<serde_json::Value>,
+}
+
+/// A visibility rule on a plugin or function: conditions combined by `conditionLogic`
+/// (e.g. `1 AND (2 OR 3)`), all of them when it is absent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleExpression {
+    pub expression_name: Option<String>,
+    pub expression_label: Option<String>,
+    pub expression_type: Option<String>,
+    pub condition_logic: Option<String>,
+    pub conditions: Option<Vec<RuleCondition>>,
+    /// Free-form formula, used when no structured conditions are given
+    pub expression: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCondition {
+    pub left_operand: Option<String>,
+    pub left_operand_type: Option<String>,
+    pub operator: Option<String>,
+    pub right_operand_value: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +92,13 @@ pub struct InstructionDefinition {
     pub description: Option<String>,
 }
 
+/// An example user utterance that should route to the plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtteranceDefinition {
+    pub name: Option<String>,
+    pub utterance: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Function {
@@ -62,11 +115,13 @@ pub struct Function {
     pub include_in_progress_indicator: Option<bool>,
     pub progress_indicator_message: Option<String>,
     pub source: Option<String>,
+    pub rule_expressions: Option<Vec<RuleExpression>>,
+    pub workflow_config: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputOutputType {
-    pub properties: Option<HashMap<String, Property>>,
+    pub properties: Option<IndexMap<String, Property>>,
     pub required: Option<Vec<String>>,
 }
 
@@ -78,6 +133,7 @@ pub struct Property {
     pub title: Option<String>,
     pub description: Option<String>,
     pub items: Option<Box<Property>>,
+    #[serde(rename = "const")]
     pub const_value: Option<serde_json::Value>,
     // Additional fields from Salesforce exports - use copilotAction: prefixed names
     #[serde(rename = "copilotAction:isUserInput")]
@@ -120,8 +176,8 @@ pub struct ActionInput {
     pub target_name: Option<String>,
     #[serde(rename = "type")]
     pub action_type: Option<String>,
-    pub inputs: Option<HashMap<String, ActionProperty>>,
-    pub outputs: Option<HashMap<String, ActionProperty>>,
+    pub inputs: Option<IndexMap<String, ActionProperty>>,
+    pub outputs: Option<IndexMap<String, ActionProperty>>,
     pub require_user_confirmation: Option<bool>,
     pub include_in_progress_indicator: Option<bool>,
     pub progress_indicator_message: Option<String>,
@@ -163,12 +219,12 @@ pub struct NGAOutput {
     pub system: SystemSection,
     pub config: ConfigSection,
     #[serde(flatten)]
-    pub topics: HashMap<String, Topic>,
-    pub variables: HashMap<String, Variable>,
+    pub topics: IndexMap<String, Topic>,
+    pub variables: IndexMap<String, Variable>,
     pub language: LanguageSection,
     pub knowledge: KnowledgeSection,
     #[serde(flatten)]
-    pub connections: HashMap<String, ConnectionSection>,
+    pub connections: IndexMap<String, ConnectionSection>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,21 +279,54 @@ pub struct ConnectionSection {
 pub struct Topic {
     pub label: String,
     pub description: String,
+    /// Routing hint built from the plugin's example utterances
+    pub classification_description: Option<String>,
     pub reasoning: ReasoningSection,
-    pub actions: Option<HashMap<String, Action>>,
+    pub actions: Option<IndexMap<String, Action>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasoningSection {
     pub instructions: String,
-    pub actions: Option<HashMap<String, ReasoningAction>>,
+    pub actions: Option<IndexMap<String, ReasoningAction>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasoningAction {
     pub target: String,
     pub description: Option<String>,
-    pub with_params: Option<Vec<String>>,
+    pub with_params: Option<Vec<WithParam>>,
+    pub set_clauses: Option<Vec<SetClause>>,
+    /// Expression guarding the action (written as an `if <condition>:` block)
+    pub condition: Option<String>,
+    /// Comment lines under the action, e.g. rules that could not be translated
+    pub comments: Option<Vec<String>>,
+}
+
+/// A `with <name> = <binding>` clause of a reasoning action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithParam {
+    pub name: String,
+    pub binding: WithBinding,
+}
+
+/// A post-action assignment `set @variables.<variable> = @outputs.<output>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetClause {
+    pub variable: String,
+    pub output: String,
+}
+
+/// Value bound to a reasoning action parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum WithBinding {
+    /// A fixed string, boolean or number (`with mode = "fast"`)
+    Literal(serde_json::Value),
+    /// A variable reference (`with contactId = @variables.ContactId`)
+    Variable(String),
+    /// Filled by the planner from the conversation (`with query = ...`)
+    SlotFill,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -249,8 +338,8 @@ pub struct Action {
     pub progress_indicator_message: Option<String>,
     pub source: Option<String>,
     pub target: String,
-    pub inputs: Option<HashMap<String, ActionInputDef>>,
-    pub outputs: Option<HashMap<String, ActionOutputDef>>,
+    pub inputs: Option<IndexMap<String, ActionInputDef>>,
+    pub outputs: Option<IndexMap<String, ActionOutputDef>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -276,6 +365,17 @@ pub struct ActionOutputDef {
     pub complex_data_type_name: Option<String>,
 }
 
+/// A name the converter changed to keep topic, action or parameter names usable and unique
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rename {
+    /// JSON path of the renamed element in the input (e.g. `plugins[2].name`)
+    pub path: String,
+    /// Input value of the name field at `path` (empty when the field is absent)
+    pub original: String,
+    /// Name it was given in the output
+    pub name: String,
+}
+
 // ============================================================================
 // RULES MODELS
 // ============================================================================
@@ -292,6 +392,11 @@ pub struct ConversionRules {
     pub connection: Option<ConnectionRules>,
     pub system: Option<SystemRules>,
     pub language: Option<LanguageRules>,
+    pub blocks: Option<BlockRules>,
+    pub config: Option<ConfigRules>,
+    pub variables: Option<VariableRules>,
+    pub topics: Option<TopicRules>,
+    pub expressions: Option<ExpressionRules>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -314,6 +419,12 @@ pub struct OutputFormatRules {
     pub indentation: Option<IndentationRules>,
     pub action_definition: Option<ActionDefinitionRules>,
     pub reasoning: Option<ReasoningFormatRules>,
+    /// `alphabetical` (default) or `source`, for variables, actions and parameters
+    pub order: Option<String>,
+    /// Per-block overrides of `action_definition.boolean_format`
+    pub language: Option<BlockFormatRules>,
+    pub knowledge: Option<BlockFormatRules>,
+    pub connection: Option<BlockFormatRules>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -327,6 +438,11 @@ pub struct ActionDefinitionRules {
     pub boolean_format: Option<BooleanFormat>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockFormatRules {
+    pub boolean_format: Option<BooleanFormat>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BooleanFormat {
     #[serde(rename = "true")]
@@ -350,6 +466,9 @@ pub struct InstructionsFormat {
 pub struct TargetFormatRules {
     pub syntax: Option<String>,
     pub mappings: Option<HashMap<String, String>>,
+    /// Transform applied to target names per invocation type
+    /// (`as_is`, `lowercase`, `snake_case` or `strip_namespace`)
+    pub name_transforms: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -372,6 +491,9 @@ pub struct Templates {
 pub struct TopicSelectorTemplate {
     pub label: Option<String>,
     pub description: Option<String>,
+    /// Description of each `go_to_<topic>` transition, with `{topic}`, `{label}`,
+    /// `{description}` and `{classification}` placeholders
+    pub transition_description: Option<String>,
     pub reasoning: Option<TemplateReasoning>,
 }
 
@@ -489,3 +611,80 @@ pub struct LanguageAllAdditionalLocales {
     #[serde(rename = "default")]
     pub default_val: Option<bool>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRules {
+    pub required: Option<Vec<String>>,
+    pub optional: Option<Vec<String>>,
+    pub order: Option<Vec<String>>,
+    /// `alphabetical` (default) or `source`
+    pub topic_order: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigRules {
+    pub required_fields: Option<Vec<String>>,
+    pub fields: Option<HashMap<String, FieldRule>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldRule {
+    #[serde(rename = "type")]
+    pub field_type: Option<String>,
+    pub description: Option<String>,
+    pub pattern: Option<String>,
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableRules {
+    pub name_rules: Option<NameRules>,
+    pub types: Option<VariableTypes>,
+    pub categories: Option<HashMap<String, VariableCategory>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameRules {
+    pub pattern: Option<String>,
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableTypes {
+    pub primitive: Option<Vec<String>>,
+    pub complex: Option<Vec<String>>,
+    pub list: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableCategory {
+    pub has_source: Option<bool>,
+    pub has_default: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicRules {
+    pub start_agent: Option<StartAgentRules>,
+    pub required_fields: Option<Vec<String>>,
+    pub optional_fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressionRules {
+    pub assignment: Option<AssignmentRules>,
+    /// Supported operators by group (comparison, logical, arithmetic, string)
+    pub operators: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentRules {
+    /// Template with `{variable}` and `{value}` placeholders, e.g. `set {variable} = {value}`
+    pub syntax: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartAgentRules {
+    pub required: Option<bool>,
+    pub name: Option<String>,
+    pub label: Option<String>,
+}
@@ -3,6 +3,14 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use crate::models::*;
+use crate::converter::{
+    plugin_action_names, plugin_escalation, plugin_topic_names, resolve_knowledge, simple_action_names,
+    simple_topic_names, KnowledgeSelection,
+};
+use crate::error::path_index;
+use crate::helpers::{format_label, strip_record_id_suffix, UNSUPPORTED_COMMENT_PREFIX};
+use crate::salesforce_id::{looks_like_id, object_type};
+use crate::validator::Diagnostic;
 
 // ============================================================================
 // STATIC REGEX PATTERNS (compiled once at startup)
@@ -44,6 +52,8 @@ pub struct ReportData {
     pub variables: Vec<VariableReport>,
     pub variables_in_instructions: VariablesInInstructions,
     pub notes: Vec<String>,
+    /// Names replaced or suffixed during conversion to stay usable and unique
+    pub renames: Vec<Rename>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +71,8 @@ pub struct AgentInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopicReport {
     pub name: String,
+    /// Name in the input when a record ID suffix was removed from it
+    pub original_name: Option<String>,
     pub label: String,
     pub description: String,
     pub is_start: bool,
@@ -70,6 +82,8 @@ pub struct TopicReport {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionReport {
     pub name: String,
+    /// Name in the input when a record ID suffix was removed from it
+    pub original_name: Option<String>,
     pub label: String,
     pub description: String,
     pub target: String,
@@ -171,56 +185,15 @@ pub fn analyze_variables_missing_descriptions(variables: &[VariableReport]) -> V
         .collect()
 }
 
-/// Check if a target name appears to be an alphanumeric ID (like a Salesforce record ID)
-/// rather than a human-readable flow API name
-fn is_alphanumeric_id(target_name: &str) -> bool {
-    // Salesforce record IDs are typically 15 or 18 characters with mixed letters and numbers
-    // Pattern: combination of letters and numbers that doesn't look like a standard name
-    // e.g., "3A7x00000004CqWEAU" or "001xx000003DGbYAAW"
-    
-    // Must have both letters and numbers
-    let has_letters = target_name.chars().any(|c| c.is_ascii_alphabetic());
-    let has_numbers = target_name.chars().any(|c| c.is_ascii_digit());
-    
-    if !has_letters || !has_numbers {
-        return false;
-    }
-    
-    // Standard flow names typically use underscores, spaces, or are PascalCase/camelCase
-    // Salesforce IDs don't have underscores or spaces
-    let has_underscore = target_name.contains('_');
-    let has_space = target_name.contains(' ');
-    
-    if has_underscore || has_space {
-        return false;
-    }
-    
-    // Check if it looks like a Salesforce ID pattern (alphanumeric, often 15-18 chars)
-    // but also catch shorter IDs that are clearly not flow names
-    let alphanumeric_only = target_name.chars().all(|c| c.is_ascii_alphanumeric());
-    
-    if !alphanumeric_only {
-        return false;
-    }
-    
-    // Heuristic: if it has consecutive numbers (like "00000") or starts with numbers,
-    // it's likely an ID rather than a flow name
-    let starts_with_number = target_name.chars().next().is_some_and(|c| c.is_ascii_digit());
-    let has_consecutive_numbers = target_name.chars()
-        .collect::<Vec<_>>()
-        .windows(3)
-        .any(|w| w.iter().all(|c| c.is_ascii_digit()));
-    
-    starts_with_number || has_consecutive_numbers
-}
-
-/// Represents a custom action (flow, apex, etc.) with alphanumeric target name that needs review
+/// Represents a custom action (flow, apex, etc.) with a record ID target that needs review
 #[derive(Debug, Clone)]
 pub struct CustomActionReview {
     pub topic_name: String,
     pub action_name: String,
     pub action_type: String,
     pub target_name: String,
+    /// Object type named by the ID's key prefix (e.g. `Flow`), if known
+    pub object_type: Option<String>,
 }
 
 /// Check if an action type is a custom action that uses external targets
@@ -243,12 +216,14 @@ pub fn analyze_custom_actions_with_alphanumeric_targets(topics: &[TopicReport])
     for topic in topics {
         for action in &topic.actions {
             // Check if action type is a custom action type (flow, apex, standardInvocableAction, etc.)
-            if is_custom_action_type(&action.action_type) && is_alphanumeric_id(&action.target) {
+            // IDs with a bad checksum are flagged too, as they still cannot be API names
+            if is_custom_action_type(&action.action_type) && looks_like_id(&action.target) {
                 results.push(CustomActionReview {
                     topic_name: topic.name.clone(),
                     action_name: action.name.clone(),
                     action_type: action.action_type.clone(),
                     target_name: action.target.clone(),
+                    object_type: object_type(&action.target).map(str::to_string),
                 });
             }
         }
@@ -266,7 +241,7 @@ pub fn generate_report_data(
     input: &AgentforceInput,
     output_yaml: &str,
     metadata: &ReportMetadata,
-) -> Result<ReportData, String> {
+) -> ReportData {
     // 1. Extract agent information
     let agent_info = extract_agent_info(input);
     
@@ -284,15 +259,18 @@ pub fn generate_report_data(
     );
     
     // 5. Generate analysis notes
-    let notes = generate_analysis_notes(&topics, &variables, metadata);
+    let knowledge = resolve_knowledge(input);
+    let unsupported = find_unsupported_comments(output_yaml);
+    let notes = generate_analysis_notes(&topics, &variables, &knowledge, &unsupported, metadata);
     
-    Ok(ReportData {
+    ReportData {
         agent_info,
         topics,
         variables,
         variables_in_instructions,
         notes,
-    })
+        renames: metadata.renames.clone(),
+    }
 }
 
 /// Extract agent information from input
@@ -309,17 +287,29 @@ fn extract_agent_info(input: &AgentforceInput) -> AgentInfo {
     }
 }
 
+/// The input name of a topic or action, if the converter removed a record ID suffix from it
+fn record_id_original(raw_name: &str) -> Option<String> {
+    (strip_record_id_suffix(raw_name) != raw_name).then(|| raw_name.to_string())
+}
+
 /// Extract topics and actions from input
+/// Converted topics and actions carry the names the converter gives them
 fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
     let mut topics = Vec::new();
     
     // Extract from plugins (Agentforce format)
     if let Some(plugins) = &input.plugins {
-        for (index, plugin) in plugins.iter().enumerate() {
+        let topic_names = plugin_topic_names(plugins, &mut Vec::new());
+        for (index, (plugin, topic_name)) in plugins.iter().zip(topic_names).enumerate() {
             if plugin.plugin_type.as_deref() == Some("TOPIC") || plugin.plugin_type.is_none() {
-                let topic_name = plugin.local_dev_name.clone()
-                    .or_else(|| Some(plugin.name.clone()))
-                    .unwrap_or_else(|| format!("topic_{}", index + 1));
+                // Plugins without a type are reported but not converted, so they keep their input names
+                let converted = topic_name.is_some();
+                let raw_name = plugin.local_dev_name.as_deref().unwrap_or(&plugin.name);
+                let (topic_name, original_name) = match topic_name {
+                    Some(topic_name) => (topic_name, record_id_original(raw_name)),
+                    None => (raw_name.to_string(), None),
+                };
+                let action_names = plugin_action_names(plugin, &path_index("plugins", index), &mut Vec::new());
                 
                 let topic_label = plugin.label.clone().unwrap_or_else(|| topic_name.clone());
                 
@@ -335,10 +325,13 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
                 
                 // Extract actions from functions
                 if let Some(functions) = &plugin.functions {
-                    for func in functions {
-                        let action_name = func.local_dev_name.clone()
-                            .or_else(|| Some(func.name.clone()))
-                            .unwrap_or_else(|| "unnamed_action".to_string());
+                    for (func, action_name) in functions.iter().zip(action_names) {
+                        let raw_name = func.local_dev_name.as_deref().unwrap_or(&func.name);
+                        let (action_name, original_name) = if converted {
+                            (action_name, record_id_original(raw_name))
+                        } else {
+                            (raw_name.to_string(), None)
+                        };
                         
                         let action_label = func.label.clone().unwrap_or_else(|| action_name.clone());
                         let action_description = func.description.clone().unwrap_or_else(|| "No description".to_string());
@@ -353,6 +346,7 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
                         
                         actions.push(ActionReport {
                             name: action_name,
+                            original_name,
                             label: action_label,
                             description: action_description,
                             target: action_target,
@@ -361,19 +355,21 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
                     }
                 }
                 
-                // Add escalation action if needed
-                if plugin.can_escalate == Some(true) {
+                // The escalation action the converter adds to the topic
+                if let Some((name, escalation)) = plugin_escalation(plugin) {
                     actions.push(ActionReport {
-                        name: "escalate_to_human".to_string(),
-                        label: "Escalate to Human".to_string(),
-                        description: "Transfer to a live human agent".to_string(),
-                        target: "@utils.escalate".to_string(),
+                        label: format_label(&name),
+                        name,
+                        original_name: None,
+                        description: escalation.description.unwrap_or_default(),
+                        target: escalation.target,
                         action_type: "escalation".to_string(),
                     });
                 }
                 
                 topics.push(TopicReport {
                     name: topic_name,
+                    original_name,
                     label: topic_label,
                     description: topic_description,
                     is_start: index == 0,
@@ -384,10 +380,9 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
     }
     // Extract from topics array (Simple format)
     else if let Some(input_topics) = &input.topics {
-        for (index, topic) in input_topics.iter().enumerate() {
-            let topic_name = topic.name.clone()
-                .or_else(|| topic.id.clone())
-                .unwrap_or_else(|| format!("topic_{}", index + 1));
+        let topic_names = simple_topic_names(input_topics, &mut Vec::new());
+        for (index, (topic, topic_name)) in input_topics.iter().zip(topic_names).enumerate() {
+            let original_name = topic.name.as_deref().or(topic.id.as_deref()).and_then(record_id_original);
             
             let topic_label = topic.label.clone().unwrap_or_else(|| topic_name.clone());
             let topic_description = topic.description.clone()
@@ -397,8 +392,11 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
             let mut actions = Vec::new();
             
             if let Some(topic_actions) = &topic.actions {
-                for action in topic_actions {
-                    let action_name = action.name.clone()
+                let action_names = simple_action_names(topic_actions, &path_index("topics", index), &mut Vec::new());
+                for (action, action_name) in topic_actions.iter().zip(action_names) {
+                    // Transitions and escalations become no action and keep their input names
+                    let action_name = action_name
+                        .or_else(|| action.name.clone())
                         .or_else(|| action.id.clone())
                         .unwrap_or_else(|| "unnamed_action".to_string());
                     
@@ -414,6 +412,7 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
                     
                     actions.push(ActionReport {
                         name: action_name,
+                        original_name: None,
                         label: action_label,
                         description: action_description,
                         target: action_target,
@@ -424,6 +423,7 @@ fn extract_topics_from_input(input: &AgentforceInput) -> Vec<TopicReport> {
             
             topics.push(TopicReport {
                 name: topic_name,
+                original_name,
                 label: topic_label,
                 description: topic_description,
                 is_start: topic.is_start == Some(true) || index == 0,
@@ -531,17 +531,19 @@ fn detect_variables_in_instructions(
 fn generate_analysis_notes(
     topics: &[TopicReport],
     variables: &[VariableReport],
+    knowledge: &KnowledgeSelection,
+    unsupported: &[String],
     metadata: &ReportMetadata,
 ) -> Vec<String> {
-    let mut notes = Vec::new();
-    
     // Agent type auto-detection note (always shown)
-    notes.push("- ⚠️ **AGENT TYPE:** The `agent_type` field was **automatically generated** based on keyword detection.".to_string());
-    notes.push("  - The converter analyzed agent data for employee-related keywords (e.g., employee, HR, internal, payroll, benefits, PTO).".to_string());
-    notes.push("  - If employee keywords were found, `agent_type` is set to `AgentforceEmployeeAgent`.".to_string());
-    notes.push("  - Otherwise, it defaults to `AgentforceServiceAgent`.".to_string());
-    notes.push("  - **Please review and manually update** the `agent_type` value if the auto-detection is incorrect.".to_string());
-    notes.push(String::new());
+    let mut notes = vec![
+        "- ⚠️ **AGENT TYPE:** The `agent_type` field was **automatically generated** based on keyword detection.".to_string(),
+        "  - The converter analyzed agent data for employee-related keywords (e.g., employee, HR, internal, payroll, benefits, PTO).".to_string(),
+        "  - If employee keywords were found, `agent_type` is set to `AgentforceEmployeeAgent`.".to_string(),
+        "  - Otherwise, it defaults to `AgentforceServiceAgent`.".to_string(),
+        "  - **Please review and manually update** the `agent_type` value if the auto-detection is incorrect.".to_string(),
+        String::new(),
+    ];
     
     // Check for missing descriptions in topics
     let topics_without_desc = analyze_topics_missing_descriptions(topics);
@@ -582,6 +584,25 @@ fn generate_analysis_notes(
         ));
     }
     
+    // Names that had a record ID suffix removed
+    let renamed: Vec<(&str, &str)> = topics
+        .iter()
+        .flat_map(|topic| {
+            std::iter::once((&topic.original_name, &topic.name))
+                .chain(topic.actions.iter().map(|action| (&action.original_name, &action.name)))
+        })
+        .filter_map(|(original, name)| original.as_deref().map(|original| (original, name.as_str())))
+        .collect();
+    if !renamed.is_empty() {
+        notes.push(format!(
+            "- {} topic/action name(s) had a Salesforce record ID suffix removed:",
+            renamed.len()
+        ));
+        for (original, name) in renamed {
+            notes.push(format!("  - `{}` → `{}`", original, name));
+        }
+    }
+    
     // Check for custom actions (flow, apex, etc.) with alphanumeric target names (likely Salesforce record IDs)
     let custom_actions_to_review = analyze_custom_actions_with_alphanumeric_targets(topics);
     if !custom_actions_to_review.is_empty() {
@@ -592,19 +613,66 @@ fn generate_analysis_notes(
         notes.push("  - **Custom actions (flow, Apex, standardInvocableAction, etc.) show the target record ID in the output.**".to_string());
         notes.push("  - **You must manually re-select the target for each action in Agentforce Builder.**".to_string());
         notes.push(String::new());
-        notes.push("  | Topic | Action | Type | Target (Record ID) |".to_string());
-        notes.push("  |-------|--------|------|-------------------|".to_string());
+        notes.push("  | Topic | Action | Type | Target (Record ID) | Object |".to_string());
+        notes.push("  |-------|--------|------|-------------------|--------|".to_string());
         for action_review in &custom_actions_to_review {
             notes.push(format!(
-                "  | `{}` | `{}` | {} | `{}` |",
+                "  | `{}` | `{}` | {} | `{}` | {} |",
                 action_review.topic_name,
                 action_review.action_name,
                 action_review.action_type,
-                action_review.target_name
+                action_review.target_name,
+                action_review.object_type.as_deref().unwrap_or("Unknown")
             ));
         }
         notes.push(String::new());
         notes.push("  - **Steps to fix:** In Agentforce Builder, navigate to each topic/action listed above and manually select the correct target from the available options.".to_string());
+        notes.push("  - **Alternatively:** add these IDs to a record ID to API name table and convert again with it (`nga --id-map <FILE>`).".to_string());
+    }
+    
+    // Several grounding libraries configured: only one fits the knowledge block
+    if knowledge.candidates.len() > 1 {
+        notes.push(format!(
+            "- ⚠️ **KNOWLEDGE:** {} grounding libraries are configured ({}); `rag_feature_config_id` uses `{}`.",
+            knowledge.candidates.len(),
+            knowledge.candidates.iter().map(|c| format!("`{}`", c)).collect::<Vec<_>>().join(", "),
+            knowledge.knowledge.rag_feature_config_id
+        ));
+        notes.push("  - **Please review** the `knowledge` block if the agent should ground on a different library.".to_string());
+    }
+    
+    // Rule expressions without an Agent Script equivalent are kept as comments
+    if !unsupported.is_empty() {
+        notes.push(format!(
+            "- ⚠️ **RULE EXPRESSIONS:** {} rule(s) could not be translated into `if` conditions and are kept as comments:",
+            unsupported.len()
+        ));
+        for comment in unsupported {
+            notes.push(format!("  - `{}`", comment));
+        }
+        notes.push("  - **Please review** these rules and add the matching conditions by hand.".to_string());
+    }
+    
+    // Cross-reference warnings (dangling transitions/action references, unreachable topics)
+    if !metadata.warnings.is_empty() {
+        notes.push(format!(
+            "- ⚠️ **CROSS-REFERENCES:** {} issue(s) found in topic transitions and action references:",
+            metadata.warnings.len()
+        ));
+        for warning in &metadata.warnings {
+            notes.push(format!("  - `{}`: {}", warning.path, warning.message));
+        }
+    }
+    
+    // Names that were empty or collided after sanitization
+    if !metadata.renames.is_empty() {
+        notes.push(format!(
+            "- ⚠️ **RENAMED:** {} name(s) were empty or collided with an earlier name after sanitization:",
+            metadata.renames.len()
+        ));
+        for rename in &metadata.renames {
+            notes.push(format!("  - `{}`: `{}` → `{}`", rename.path, rename.original, rename.name));
+        }
     }
     
     // Conversion metadata notes
@@ -615,6 +683,17 @@ fn generate_analysis_notes(
     notes
 }
 
+/// Find the comments the converter wrote for rules it could not translate
+fn find_unsupported_comments(output_yaml: &str) -> Vec<String> {
+    let prefix = format!("# {}", UNSUPPORTED_COMMENT_PREFIX);
+    output_yaml
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with(&prefix))
+        .map(|line| line[2..].to_string())
+        .collect()
+}
+
 // ============================================================================
 // REPORT METADATA STRUCTURE
 // ============================================================================
@@ -627,6 +706,12 @@ pub struct ReportMetadata {
     pub has_variables_with_dollar: bool,
     pub alert_message: Option<String>,
     pub status_suffix: Option<String>,
+    /// Cross-reference warnings from the conversion
+    #[serde(default)]
+    pub warnings: Vec<Diagnostic>,
+    /// Names replaced or suffixed during the conversion to stay usable and unique
+    #[serde(default)]
+    pub renames: Vec<Rename>,
 }
 
 // ============================================================================
@@ -637,30 +722,6 @@ pub struct ReportMetadata {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_is_alphanumeric_id_salesforce_record_id() {
-        // Typical Salesforce record IDs (15 or 18 characters)
-        assert!(is_alphanumeric_id("3A7x00000004CqWEAU"));
-        assert!(is_alphanumeric_id("001xx000003DGbYAAW"));
-        assert!(is_alphanumeric_id("172Wt00000HG6ShIAL"));
-    }
-
-    #[test]
-    fn test_is_alphanumeric_id_api_names_are_not_ids() {
-        // API names with underscores are NOT record IDs
-        assert!(!is_alphanumeric_id("SvcCopilotTmpl__GetCaseByCaseNumber"));
-        assert!(!is_alphanumeric_id("MyFlow_v1"));
-        assert!(!is_alphanumeric_id("Get_Customer_Cases"));
-    }
-
-    #[test]
-    fn test_is_alphanumeric_id_regular_names_are_not_ids() {
-        // Regular flow names should NOT be detected as IDs
-        assert!(!is_alphanumeric_id("GetCaseByCaseNumber"));
-        assert!(!is_alphanumeric_id("MyTestFlow"));
-        assert!(!is_alphanumeric_id("CustomerService"));
-    }
-
     #[test]
     fn test_is_custom_action_type() {
         // Custom action types that should be detected
@@ -686,15 +747,17 @@ mod tests {
         let topics = vec![
             TopicReport {
                 name: "case_management".to_string(),
+                original_name: None,
                 label: "Case Management".to_string(),
                 description: "Handles cases".to_string(),
                 is_start: false,
                 actions: vec![
                     ActionReport {
                         name: "GetCase".to_string(),
+                        original_name: None,
                         label: "Get Case".to_string(),
                         description: "Gets a case".to_string(),
-                        target: "3A7x00000004CqWEAU".to_string(), // Record ID
+                        target: "3A7x00000004CqWCAU".to_string(), // Record ID
                         action_type: "flow".to_string(),
                     },
                 ],
@@ -705,7 +768,8 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].action_name, "GetCase");
         assert_eq!(results[0].action_type, "flow");
-        assert_eq!(results[0].target_name, "3A7x00000004CqWEAU");
+        assert_eq!(results[0].target_name, "3A7x00000004CqWCAU");
+        assert_eq!(results[0].object_type, None);
     }
 
     #[test]
@@ -713,12 +777,14 @@ mod tests {
         let topics = vec![
             TopicReport {
                 name: "customer_service".to_string(),
+                original_name: None,
                 label: "Customer Service".to_string(),
                 description: "Handles customer inquiries".to_string(),
                 is_start: false,
                 actions: vec![
                     ActionReport {
                         name: "SendEmail".to_string(),
+                        original_name: None,
                         label: "Send Email".to_string(),
                         description: "Sends an email".to_string(),
                         target: "001xx000003DGbYAAW".to_string(), // Record ID
@@ -732,6 +798,35 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].action_name, "SendEmail");
         assert_eq!(results[0].action_type, "apex");
+        assert_eq!(results[0].object_type.as_deref(), Some("Account"));
+    }
+
+    #[test]
+    fn test_analyze_custom_actions_detects_id_with_bad_checksum() {
+        let topics = vec![
+            TopicReport {
+                name: "orders".to_string(),
+                original_name: None,
+                label: "Orders".to_string(),
+                description: "Handles orders".to_string(),
+                is_start: false,
+                actions: vec![
+                    ActionReport {
+                        name: "GetOrder".to_string(),
+                        original_name: None,
+                        label: "Get Order".to_string(),
+                        description: "Gets an order".to_string(),
+                        target: "172Wt00000HG6ShIAA".to_string(), // Checksum should be IAL
+                        action_type: "flow".to_string(),
+                    },
+                ],
+            },
+        ];
+
+        let results = analyze_custom_actions_with_alphanumeric_targets(&topics);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].target_name, "172Wt00000HG6ShIAA");
+        assert_eq!(results[0].object_type, None);
     }
 
     #[test]
@@ -739,12 +834,14 @@ mod tests {
         let topics = vec![
             TopicReport {
                 name: "case_management".to_string(),
+                original_name: None,
                 label: "Case Management".to_string(),
                 description: "Handles cases".to_string(),
                 is_start: false,
                 actions: vec![
                     ActionReport {
                         name: "GetCase".to_string(),
+                        original_name: None,
                         label: "Get Case".to_string(),
                         description: "Gets a case".to_string(),
                         target: "SvcCopilotTmpl__GetCaseByCaseNumber".to_string(), // API name
@@ -763,15 +860,17 @@ mod tests {
         let topics = vec![
             TopicReport {
                 name: "escalation".to_string(),
+                original_name: None,
                 label: "Escalation".to_string(),
                 description: "Handles escalation".to_string(),
                 is_start: false,
                 actions: vec![
                     ActionReport {
                         name: "Escalate".to_string(),
+                        original_name: None,
                         label: "Escalate".to_string(),
                         description: "Escalates to human".to_string(),
-                        target: "3A7x00000004CqWEAU".to_string(), // Record ID but escalation type
+                        target: "3A7x00000004CqWCAU".to_string(), // Record ID but escalation type
                         action_type: "escalation".to_string(),
                     },
                 ],
@@ -781,4 +880,147 @@ mod tests {
         let results = analyze_custom_actions_with_alphanumeric_targets(&topics);
         assert_eq!(results.len(), 0); // Escalation type is not a custom action
     }
+
+    #[test]
+    fn test_analysis_notes_include_reference_warnings() {
+        let metadata: ReportMetadata = serde_json::from_str(r#"{
+            "input_format": "json",
+            "topic_count": 2,
+            "action_count": 0,
+            "has_variables_with_dollar": false,
+            "warnings": [{
+                "severity": "warning",
+                "path": "topic billing",
+                "message": "Topic 'billing' is unreachable: no transition targets it"
+            }]
+        }"#).unwrap();
+
+        let knowledge = KnowledgeSelection {
+            knowledge: KnowledgeSection { rag_feature_config_id: String::new(), citations_enabled: false },
+            candidates: vec![],
+        };
+        let notes = generate_analysis_notes(&[], &[], &knowledge, &[], &metadata);
+        assert!(notes.contains(&"- ⚠️ **CROSS-REFERENCES:** 1 issue(s) found in topic transitions and action references:".to_string()));
+        assert!(notes.contains(&"  - `topic billing`: Topic 'billing' is unreachable: no transition targets it".to_string()));
+    }
+
+    #[test]
+    fn test_report_lists_name_collisions() {
+        let input: AgentforceInput = serde_json::from_str(r#"{
+            "plugins": [
+                {"name": "My Topic", "pluginType": "TOPIC"},
+                {"name": "My-Topic", "pluginType": "TOPIC"}
+            ]
+        }"#).unwrap();
+        let outcome = crate::convert(&input, None, None);
+        let metadata = ReportMetadata {
+            input_format: "json".to_string(),
+            topic_count: outcome.topic_count,
+            action_count: outcome.action_count,
+            has_variables_with_dollar: false,
+            alert_message: None,
+            status_suffix: None,
+            warnings: vec![],
+            renames: outcome.renames,
+        };
+
+        let report = generate_report_data(&input, &outcome.yaml, &metadata);
+        assert_eq!(report.renames.len(), 1);
+        assert!(report.notes.contains(&"- ⚠️ **RENAMED:** 1 name(s) were empty or collided with an earlier name after sanitization:".to_string()));
+        assert!(report.notes.contains(&"  - `plugins[1].name`: `My-Topic` → `my_topic_2`".to_string()));
+    }
+
+    #[test]
+    fn test_analysis_notes_report_knowledge_choice() {
+        let input: AgentforceInput = serde_json::from_str(r#"{
+            "adlPlannerConfigurations": [
+                {"ragFeatureName": "Library_A"},
+                {"ragFeatureId": "1JeKc00000000G4KAI"},
+                {"ragFeatureName": "Library_A"}
+            ]
+        }"#).unwrap();
+        let metadata: ReportMetadata = serde_json::from_str(
+            r#"{"input_format": "json", "topic_count": 0, "action_count": 0, "has_variables_with_dollar": false}"#
+        ).unwrap();
+
+        let knowledge = resolve_knowledge(&input);
+        assert_eq!(knowledge.knowledge.rag_feature_config_id, "Library_A");
+        assert!(!knowledge.knowledge.citations_enabled);
+
+        let notes = generate_analysis_notes(&[], &[], &knowledge, &[], &metadata);
+        assert!(notes.contains(&"- ⚠️ **KNOWLEDGE:** 2 grounding libraries are configured (`Library_A`, `1JeKc00000000G4KAI`); `rag_feature_config_id` uses `Library_A`.".to_string()));
+    }
+
+    #[test]
+    fn test_analysis_notes_list_unsupported_rules() {
+        let metadata: ReportMetadata = serde_json::from_str(
+            r#"{"input_format": "json", "topic_count": 0, "action_count": 0, "has_variables_with_dollar": false}"#
+        ).unwrap();
+        let knowledge = KnowledgeSelection {
+            knowledge: KnowledgeSection { rag_feature_config_id: String::new(), citations_enabled: false },
+            candidates: vec![],
+        };
+        let output = "            CloseCase: @actions.CloseCase\n                # Unsupported workflowConfig: {\"flow\":\"Route\"}\n";
+
+        let unsupported = find_unsupported_comments(output);
+        let notes = generate_analysis_notes(&[], &[], &knowledge, &unsupported, &metadata);
+        assert!(notes.contains(&"- ⚠️ **RULE EXPRESSIONS:** 1 rule(s) could not be translated into `if` conditions and are kept as comments:".to_string()));
+        assert!(notes.contains(&"  - `Unsupported workflowConfig: {\"flow\":\"Route\"}`".to_string()));
+    }
+
+    #[test]
+    fn test_record_id_suffixes_reported_with_original_names() {
+        let input: AgentforceInput = serde_json::from_str(r#"{
+            "plugins": [{
+                "name": "General_Web_Search_16jKc0000004Cqw",
+                "pluginType": "TOPIC",
+                "functions": [
+                    {"name": "179Kc0000004HuX", "label": "Answer Questions"},
+                    {"name": "GetOrder"}
+                ]
+            }]
+        }"#).unwrap();
+        let metadata: ReportMetadata = serde_json::from_str(
+            r#"{"input_format": "json", "topic_count": 1, "action_count": 2, "has_variables_with_dollar": false}"#
+        ).unwrap();
+
+        let report = generate_report_data(&input, "", &metadata);
+        let topic = &report.topics[0];
+        // Reported names are the ones in the converted script
+        let outcome = crate::convert(&input, None, None);
+        let actions = outcome.nga.topics["topic general_web_search"].actions.as_ref().unwrap();
+        assert_eq!(actions.keys().collect::<Vec<_>>(), ["Answer_Questions", "GetOrder"]);
+
+        assert_eq!(topic.name, "general_web_search");
+        assert_eq!(topic.original_name.as_deref(), Some("General_Web_Search_16jKc0000004Cqw"));
+        assert_eq!(topic.actions[0].name, "Answer_Questions");
+        assert_eq!(topic.actions[0].original_name.as_deref(), Some("179Kc0000004HuX"));
+        assert_eq!(topic.actions[1].name, "GetOrder");
+        assert_eq!(topic.actions[1].original_name, None);
+
+        assert!(report.notes.contains(&"- 2 topic/action name(s) had a Salesforce record ID suffix removed:".to_string()));
+        assert!(report.notes.contains(&"  - `General_Web_Search_16jKc0000004Cqw` → `general_web_search`".to_string()));
+        assert!(report.notes.contains(&"  - `179Kc0000004HuX` → `Answer_Questions`".to_string()));
+    }
+
+    #[test]
+    fn test_escalation_reported_only_when_emitted() {
+        let input: AgentforceInput = serde_json::from_str(r#"{
+            "plugins": [
+                {"name": "Support", "pluginType": "TOPIC", "canEscalate": true},
+                {
+                    "name": "Billing",
+                    "pluginType": "TOPIC",
+                    "canEscalate": true,
+                    "functions": [{"name": "escalate_to_human", "invocationTargetType": "flow"}]
+                }
+            ]
+        }"#).unwrap();
+
+        let topics = extract_topics_from_input(&input);
+        let support: Vec<_> = topics[0].actions.iter().map(|a| (a.name.as_str(), a.target.as_str())).collect();
+        assert_eq!(support, [("escalate_to_human", "@utils.escalate")]);
+        let billing: Vec<_> = topics[1].actions.iter().map(|a| a.action_type.as_str()).collect();
+        assert_eq!(billing, ["flow"]);
+    }
 }
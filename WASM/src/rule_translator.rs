@@ -0,0 +1,309 @@
+//! Translation of Agentforce visibility rules into Agent Script conditions
+//!
+//! `ruleExpressions` on plugins and functions become the condition of an
+//! `if <condition>:` block around the transition or action they guard.
+//! Rules that cannot be expressed with the operators listed under
+//! `expressions.operators` in the rules file (and any `workflowConfig`) are
+//! kept as comments instead.
+
+use crate::models::*;
+use crate::helpers::*;
+use crate::script_writer::quote;
+use crate::yaml_generator::{get_boolean_format, BooleanBlock};
+
+/// Operators supported when the rules do not list any
+const DEFAULT_OPERATORS: &[&str] = &[
+    "==", "!=", "<", "<=", ">", ">=", "is", "is not", "and", "or", "not", "contains", "starts_with", "ends_with",
+];
+
+/// Condition and comments for one guarded action or transition
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Guard {
+    /// All translated rules joined with `and`
+    pub condition: Option<String>,
+    /// One comment per rule that could not be translated
+    pub comments: Vec<String>,
+}
+
+impl Guard {
+    /// Apply the guard to a reasoning action
+    pub fn apply(self, action: &mut ReasoningAction) {
+        action.condition = self.condition;
+        action.comments = if self.comments.is_empty() { None } else { Some(self.comments) };
+    }
+}
+
+/// Translate the rules of a plugin or function
+pub fn translate_rules(
+    expressions: Option<&[RuleExpression]>,
+    workflow_config: Option<&serde_json::Value>,
+    rules: Option<&ConversionRules>,
+) -> Guard {
+    let operators = get_supported_operators(rules);
+    let mut conditions = Vec::new();
+    let mut guard = Guard::default();
+
+    for expression in expressions.unwrap_or_default() {
+        match translate_expression(expression, &operators, rules) {
+            Ok(Some(condition)) => conditions.push(condition),
+            Ok(None) => {}
+            Err(()) => {
+                let name = expression.expression_name.as_deref().unwrap_or("unnamed");
+                let raw = serde_json::to_string(expression).unwrap_or_default();
+                guard.comments.push(format!("{} rule expression '{}': {}", UNSUPPORTED_COMMENT_PREFIX, name, raw));
+            }
+        }
+    }
+
+    if let Some(config) = workflow_config.filter(|c| !is_empty_json(c)) {
+        guard.comments.push(format!("{} workflowConfig: {}", UNSUPPORTED_COMMENT_PREFIX, config));
+    }
+
+    guard.condition = match conditions.len() {
+        0 => None,
+        1 => conditions.pop(),
+        _ => Some(
+            conditions
+                .iter()
+                .map(|c| if c.contains(" or ") { format!("({})", c) } else { c.clone() })
+                .collect::<Vec<_>>()
+                .join(" and "),
+        ),
+    };
+    guard
+}
+
+/// Variables compared by translatable conditions, so the converter can define them
+pub fn condition_variables(expressions: Option<&[RuleExpression]>) -> Vec<String> {
+    expressions
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|e| e.conditions.iter().flatten())
+        .filter_map(|c| variable_name(c.left_operand.as_deref()?))
+        .collect()
+}
+
+/// Get the operators the target supports from `expressions.operators`
+fn get_supported_operators(rules: Option<&ConversionRules>) -> Vec<String> {
+    if let Some(rules) = rules {
+        if let Some(expressions) = &rules.expressions {
+            if let Some(operators) = &expressions.operators {
+                return operators.values().flatten().cloned().collect();
+            }
+        }
+    }
+    DEFAULT_OPERATORS.iter().map(|s| s.to_string()).collect()
+}
+
+/// Translate one rule expression
+/// Ok(None) for a rule without conditions, Err(()) when it cannot be translated
+fn translate_expression(
+    expression: &RuleExpression,
+    operators: &[String],
+    rules: Option<&ConversionRules>,
+) -> Result<Option<String>, ()> {
+    let conditions = match &expression.conditions {
+        Some(conditions) if !conditions.is_empty() => conditions,
+        // A free-form formula has no structured form to translate
+        _ if expression.expression.as_deref().is_some_and(|e| !e.trim().is_empty()) => return Err(()),
+        _ => return Ok(None),
+    };
+
+    let translated = conditions
+        .iter()
+        .map(|c| translate_condition(c, operators, rules))
+        .collect::<Result<Vec<_>, ()>>()?;
+
+    match expression.condition_logic.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        None => {
+            if !supports(operators, "and") && translated.len() > 1 {
+                return Err(());
+            }
+            Ok(Some(translated.join(" and ")))
+        }
+        Some(logic) => translate_logic(logic, &translated, operators).map(Some),
+    }
+}
+
+/// Translate a `conditionLogic` string (`1 AND (2 OR NOT 3)`) over translated conditions
+fn translate_logic(logic: &str, conditions: &[String], operators: &[String]) -> Result<String, ()> {
+    let spaced = logic.replace('(', " ( ").replace(')', " ) ");
+    let mut parts = Vec::new();
+    for token in spaced.split_whitespace() {
+        let part = match token.to_ascii_lowercase().as_str() {
+            word @ ("and" | "or" | "not") if supports(operators, word) => word.to_string(),
+            "(" | ")" => token.to_string(),
+            number => {
+                let index: usize = number.parse().map_err(|_| ())?;
+                let condition = index.checked_sub(1).and_then(|i| conditions.get(i)).ok_or(())?;
+                if conditions.len() > 1 { format!("({})", condition) } else { condition.clone() }
+            }
+        };
+        parts.push(part);
+    }
+    Ok(parts.join(" ").replace("( ", "(").replace(" )", ")"))
+}
+
+/// Translate a single `left operator right` condition
+fn translate_condition(
+    condition: &RuleCondition,
+    operators: &[String],
+    rules: Option<&ConversionRules>,
+) -> Result<String, ()> {
+    let variable = condition.left_operand.as_deref().and_then(variable_name).ok_or(())?;
+    let operator = condition.operator.as_deref().unwrap_or("equal");
+    let (operator, right) = match map_operator(operator).ok_or(())? {
+        (operator, true) => (operator, format_operand(condition.right_operand_value.as_ref(), rules)?),
+        (operator, false) => (operator, "None".to_string()),
+    };
+    if !supports(operators, operator) {
+        return Err(());
+    }
+    Ok(format!("{}{} {} {}", VARIABLE_REFERENCE_PREFIX, variable, operator, right))
+}
+
+/// Map an Agentforce operator to an Agent Script operator
+/// The flag tells whether the operator takes a right operand
+fn map_operator(operator: &str) -> Option<(&'static str, bool)> {
+    let normalized: String = operator.chars().filter(|c| *c != '_' && *c != ' ').collect::<String>().to_ascii_lowercase();
+    let mapped = match normalized.as_str() {
+        "equal" | "equals" | "eq" | "==" | "=" => ("==", true),
+        "notequal" | "notequals" | "ne" | "!=" => ("!=", true),
+        "lessthan" | "lt" | "<" => ("<", true),
+        "lessthanorequal" | "lessorequal" | "le" | "<=" => ("<=", true),
+        "greaterthan" | "gt" | ">" => (">", true),
+        "greaterthanorequal" | "greaterorequal" | "ge" | ">=" => (">=", true),
+        "contains" => ("contains", true),
+        "startswith" => ("starts_with", true),
+        "endswith" => ("ends_with", true),
+        "isnull" | "isempty" => ("is", false),
+        "isnotnull" | "isnotempty" => ("is not", false),
+        _ => return None,
+    };
+    Some(mapped)
+}
+
+/// Format the right operand: booleans in the actions boolean format, numbers bare, text quoted
+fn format_operand(value: Option<&serde_json::Value>, rules: Option<&ConversionRules>) -> Result<String, ()> {
+    let (true_val, false_val) = get_boolean_format(rules, BooleanBlock::Actions);
+    match value {
+        Some(serde_json::Value::Bool(b)) => Ok(if *b { true_val } else { false_val }),
+        Some(serde_json::Value::Number(n)) => Ok(n.to_string()),
+        Some(serde_json::Value::String(s)) => {
+            if s.eq_ignore_ascii_case("true") {
+                Ok(true_val)
+            } else if s.eq_ignore_ascii_case("false") {
+                Ok(false_val)
+            } else if serde_json::from_str::<serde_json::Number>(s).is_ok() {
+                Ok(s.clone())
+            } else if s.chars().any(char::is_control) {
+                Err(())
+            } else {
+                Ok(quote(s))
+            }
+        }
+        _ => Err(()),
+    }
+}
+
+/// The variable a left operand names: `Verified`, `$Verified` or `{!$Verified}`
+/// Dotted paths (`$Context.Verified`) have no variable to map to
+fn variable_name(operand: &str) -> Option<String> {
+    let name = operand.trim().trim_start_matches("{!").trim_end_matches('}').trim_start_matches('$');
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| name.to_string())
+}
+
+/// Whether an operator is supported
+fn supports(operators: &[String], operator: &str) -> bool {
+    operators.iter().any(|o| o == operator)
+}
+
+/// Null, empty objects and empty arrays carry no configuration
+fn is_empty_json(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Object(map) => map.is_empty(),
+        serde_json::Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expression(json: &str) -> RuleExpression {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_conditions_joined_by_logic() {
+        let rule = expression(r#"{
+            "expressionName": "Verified_Gold",
+            "conditionLogic": "1 AND (2 OR NOT 3)",
+            "conditions": [
+                {"leftOperand": "{!$Verified}", "operator": "equal", "rightOperandValue": "true"},
+                {"leftOperand": "Tier", "operator": "equals", "rightOperandValue": "Gold"},
+                {"leftOperand": "$Balance", "operator": "greaterThan", "rightOperandValue": 100}
+            ]
+        }"#);
+        let guard = translate_rules(Some(&[rule]), None, None);
+        assert_eq!(
+            guard.condition.as_deref(),
+            Some("(@variables.Verified == True) and ((@variables.Tier == \"Gold\") or not (@variables.Balance > 100))")
+        );
+        assert!(guard.comments.is_empty());
+    }
+
+    #[test]
+    fn test_rules_combined_with_and() {
+        let rules = [
+            expression(r#"{"conditions": [{"leftOperand": "Verified", "operator": "isNotNull"}]}"#),
+            expression(r#"{"conditions": [
+                {"leftOperand": "A", "operator": "equal", "rightOperandValue": 1},
+                {"leftOperand": "B", "operator": "equal", "rightOperandValue": 2}
+            ], "conditionLogic": "1 OR 2"}"#),
+        ];
+        let guard = translate_rules(Some(&rules), None, None);
+        assert_eq!(
+            guard.condition.as_deref(),
+            Some("@variables.Verified is not None and ((@variables.A == 1) or (@variables.B == 2))")
+        );
+        assert_eq!(condition_variables(Some(&rules)), ["Verified", "A", "B"]);
+    }
+
+    #[test]
+    fn test_unsupported_rules_become_comments() {
+        let rules = [
+            expression(r#"{"expressionName": "Formula", "expression": "ISPICKVAL(Status, 'Open')"}"#),
+            expression(r#"{"expressionName": "Dotted", "conditions": [{"leftOperand": "$Context.Verified", "operator": "equal", "rightOperandValue": true}]}"#),
+            expression(r#"{"expressionName": "Regex", "conditions": [{"leftOperand": "Email", "operator": "matches", "rightOperandValue": ".*"}]}"#),
+            expression(r#"{"expressionName": "Empty", "conditions": []}"#),
+        ];
+        let config = serde_json::json!({"flow": "Route_Case"});
+        let guard = translate_rules(Some(&rules), Some(&config), None);
+        assert_eq!(guard.condition, None);
+        assert_eq!(guard.comments.len(), 4);
+        assert!(guard.comments[0].starts_with("Unsupported rule expression 'Formula': {"));
+        assert_eq!(guard.comments[3], "Unsupported workflowConfig: {\"flow\":\"Route_Case\"}");
+    }
+
+    #[test]
+    fn test_operators_from_rules() {
+        let rules = crate::parse_rules(r#"{"expressions": {"operators": {"comparison": ["=="]}}}"#).unwrap();
+        let rule = expression(r#"{"conditions": [
+            {"leftOperand": "A", "operator": "equal", "rightOperandValue": "x"},
+            {"leftOperand": "B", "operator": "notEqual", "rightOperandValue": "y"}
+        ]}"#);
+        let guard = translate_rules(Some(&[rule]), None, rules.as_ref());
+        assert_eq!(guard.condition, None);
+        assert_eq!(guard.comments.len(), 1);
+    }
+}
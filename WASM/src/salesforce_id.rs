@@ -0,0 +1,185 @@
+//! Salesforce record IDs
+//!
+//! A record ID is 15 case-sensitive characters: a 3-character key prefix naming
+//! the object type, a 2-character instance, a reserved `0` and a 9-character
+//! record number. The 18-character case-insensitive form appends a 3-character
+//! checksum encoding which of the first 15 characters are uppercase.
+
+/// Alphabet of the 18-character checksum suffix
+const CHECKSUM_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ012345";
+
+/// Object types behind well-known key prefixes
+const KEY_PREFIXES: &[(&str, &str)] = &[
+    ("001", "Account"),
+    ("003", "Contact"),
+    ("005", "User"),
+    ("006", "Opportunity"),
+    ("00D", "Organization"),
+    ("00Q", "Lead"),
+    ("01p", "ApexClass"),
+    ("16j", "GenAiPlugin"),
+    ("172", "GenAiFunction"),
+    ("179", "GenAiFunctionDefinition"),
+    ("300", "Flow"),
+    ("301", "FlowDefinition"),
+    ("500", "Case"),
+];
+
+/// Check if a value is a 15-character record ID body
+fn is_id15(value: &str) -> bool {
+    value.len() == 15
+        && value.chars().all(|c| c.is_ascii_alphanumeric())
+        && value.as_bytes()[5] == b'0'
+}
+
+/// Compute the 3-character checksum suffix of a 15-character ID
+fn checksum(id15: &str) -> String {
+    id15.as_bytes()
+        .chunks(5)
+        .map(|chunk| {
+            let bits = chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_ascii_uppercase())
+                .fold(0, |bits, (i, _)| bits | (1 << i));
+            CHECKSUM_ALPHABET[bits] as char
+        })
+        .collect()
+}
+
+/// Check if a value is a Salesforce record ID
+/// 15-character IDs are checked for shape, 18-character IDs also for their checksum
+pub fn is_salesforce_id(value: &str) -> bool {
+    if !value.is_ascii() {
+        return false;
+    }
+    match value.len() {
+        15 => is_id15(value),
+        18 => is_id15(&value[..15]) && checksum(&value[..15]) == value[15..].to_ascii_uppercase(),
+        _ => false,
+    }
+}
+
+/// Check if a value has the shape of a record ID, ignoring the 18-character checksum
+/// An 18-character value with a bad checksum is usually a mistyped or hand-edited ID
+pub fn looks_like_id(value: &str) -> bool {
+    if !value.is_ascii() {
+        return false;
+    }
+    match value.len() {
+        15 => is_id15(value),
+        18 => is_id15(&value[..15]) && value[15..].chars().all(|c| c.is_ascii_alphanumeric()),
+        _ => false,
+    }
+}
+
+/// Convert a record ID to its 18-character form
+/// Returns None if the value is not a record ID
+pub fn to_18(value: &str) -> Option<String> {
+    if !is_salesforce_id(value) {
+        return None;
+    }
+    let id15 = &value[..15];
+    Some(format!("{}{}", id15, checksum(id15)))
+}
+
+/// Name the object type behind a record ID's key prefix
+/// Returns None if the value is not a record ID or the prefix is not known
+pub fn object_type(value: &str) -> Option<&'static str> {
+    if !is_salesforce_id(value) {
+        return None;
+    }
+    KEY_PREFIXES
+        .iter()
+        .find(|(prefix, _)| value.starts_with(prefix))
+        .map(|(_, object)| *object)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_validation() {
+        assert!(is_salesforce_id("001xx000003DGbY"));
+        assert!(is_salesforce_id("001xx000003DGbYAAW"));
+        assert!(is_salesforce_id("172Wt00000HG6ShIAL"));
+        // The suffix is case-insensitive, the body is not
+        assert!(is_salesforce_id("172Wt00000HG6Shial"));
+        assert!(!is_salesforce_id("172Wt00000HG6ShIAA"));
+        assert!(!is_salesforce_id("172wt00000HG6ShIAL"));
+
+        // Names of the same length are not IDs
+        assert!(!is_salesforce_id("CustomerService"));
+        assert!(!is_salesforce_id("Get_Order_Status__c"));
+        assert!(!is_salesforce_id("GetCaseByCaseNumber"));
+        assert!(!is_salesforce_id("Café_Orders_Bot"));
+    }
+
+    #[test]
+    fn test_salesforce_record_ids() {
+        // Typical Salesforce record IDs (15 or 18 characters)
+        assert!(is_salesforce_id("3A7x00000004CqWCAU"));
+        assert!(is_salesforce_id("001xx000003DGbYAAW"));
+        assert!(is_salesforce_id("172Wt00000HG6ShIAL"));
+    }
+
+    #[test]
+    fn test_api_names_are_not_ids() {
+        // API names with underscores are NOT record IDs
+        assert!(!is_salesforce_id("SvcCopilotTmpl__GetCaseByCaseNumber"));
+        assert!(!is_salesforce_id("MyFlow_v1"));
+        assert!(!is_salesforce_id("Get_Customer_Cases"));
+    }
+
+    #[test]
+    fn test_regular_names_are_not_ids() {
+        // Regular flow names should NOT be detected as IDs
+        assert!(!is_salesforce_id("GetCaseByCaseNumber"));
+        assert!(!is_salesforce_id("MyTestFlow"));
+        assert!(!is_salesforce_id("CustomerService"));
+    }
+
+    #[test]
+    fn test_id_shape_without_checksum() {
+        assert!(looks_like_id("172Wt00000HG6ShIAL"));
+        assert!(looks_like_id("172Wt00000HG6ShIAA"));
+        assert!(!is_salesforce_id("172Wt00000HG6ShIAA"));
+        assert!(looks_like_id("001xx000003DGbY"));
+        assert!(!looks_like_id("172Wt00000HG6Sh_AL"));
+        assert!(!looks_like_id("Get_Order_Status__c"));
+        assert!(!looks_like_id("CustomerService"));
+    }
+
+    #[test]
+    fn test_multibyte_input_is_not_an_id() {
+        // 18 bytes but 9 characters: slicing at byte 15 would split a character
+        let value = "ééééééééé";
+        assert_eq!(value.len(), 18);
+        assert!(!is_salesforce_id(value));
+        assert_eq!(to_18(value), None);
+        assert_eq!(object_type(value), None);
+        assert!(!looks_like_id(value));
+    }
+
+    #[test]
+    fn test_to_18() {
+        assert_eq!(to_18("001xx000003DGbY").as_deref(), Some("001xx000003DGbYAAW"));
+        assert_eq!(to_18("300xx0000001AbCaau").as_deref(), Some("300xx0000001AbCAAU"));
+        assert_eq!(to_18("MyTestFlow"), None);
+    }
+
+    #[test]
+    fn test_object_type_from_key_prefix() {
+        assert_eq!(object_type("300xx0000001AbCAAU"), Some("Flow"));
+        assert_eq!(object_type("01pxx0000002XyZ"), Some("ApexClass"));
+        assert_eq!(object_type("172Wt00000HG6ShIAL"), Some("GenAiFunction"));
+        assert_eq!(object_type("16jxx0000000001"), Some("GenAiPlugin"));
+        assert_eq!(object_type("3A7x00000004CqWCAU"), None);
+        assert_eq!(object_type("300_Flow"), None);
+    }
+}
@@ -0,0 +1,993 @@
+use std::collections::HashSet;
+use indexmap::IndexMap;
+use crate::models::*;
+use crate::helpers::*;
+use crate::error::ScriptError;
+use crate::yaml_generator::{
+    assignment_keyword, get_assignment_syntax, get_boolean_format, get_instruction_indicator,
+    get_instruction_line_prefix, BooleanBlock,
+};
+
+// ============================================================================
+// SCRIPT TREE
+// ============================================================================
+
+/// A non-blank line of Agent Script text
+struct SourceLine<'a> {
+    /// 1-based line number
+    number: usize,
+    /// Number of leading spaces
+    indent: usize,
+    /// Line text after the indentation
+    text: &'a str,
+}
+
+/// A `key: value` entry together with its nested entries
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    /// Raw value text after the colon (still quoted), if any
+    value: Option<String>,
+    line: usize,
+    key_column: usize,
+    value_column: usize,
+    children: Vec<Entry>,
+    /// Lines of an `instructions: ->` block, with the line prefix removed
+    block: Option<Vec<String>>,
+    /// `# comment` lines nested under this entry, without the `#`
+    comments: Vec<String>,
+}
+
+impl Entry {
+    /// Build an error positioned at this entry's key
+    fn error_at_key(&self, message: impl Into<String>) -> ScriptError {
+        ScriptError::new(self.line, self.key_column, message)
+    }
+
+    /// Build an error positioned at this entry's value
+    fn error_at_value(&self, message: impl Into<String>) -> ScriptError {
+        ScriptError::new(self.line, self.value_column, message)
+    }
+}
+
+/// Split script text into non-blank source lines
+fn collect_lines(script: &str) -> Result<Vec<SourceLine<'_>>, ScriptError> {
+    let mut lines = Vec::new();
+
+    for (index, raw) in script.lines().enumerate() {
+        if raw.trim().is_empty() {
+            continue;
+        }
+
+        let text = raw.trim_start_matches(' ');
+        let indent = raw.len() - text.len();
+        if text.starts_with('\t') {
+            return Err(ScriptError::new(index + 1, indent + 1, "Tabs are not allowed in indentation"));
+        }
+
+        lines.push(SourceLine { number: index + 1, indent, text });
+    }
+
+    Ok(lines)
+}
+
+/// Parse sibling entries sharing the indentation of the line at `pos`
+/// `keyword` starts assignment statements (`set`), read like `with` clauses
+/// Comment lines among the siblings are added to `comments`
+fn parse_entries(
+    lines: &[SourceLine<'_>],
+    pos: &mut usize,
+    indicator: &str,
+    line_prefix: &str,
+    keyword: &str,
+    comments: &mut Vec<String>,
+) -> Result<Vec<Entry>, ScriptError> {
+    let indent = lines[*pos].indent;
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut seen_keys = HashSet::new();
+
+    while *pos < lines.len() {
+        let line = &lines[*pos];
+        if line.indent < indent {
+            break;
+        }
+        if let Some(comment) = line.text.strip_prefix('#') {
+            comments.push(comment.trim().to_string());
+            *pos += 1;
+            continue;
+        }
+        if line.indent > indent {
+            return Err(ScriptError::new(line.number, line.indent + 1, "Unexpected indentation"));
+        }
+
+        let mut entry = parse_entry_line(line, keyword)?;
+        *pos += 1;
+
+        let is_statement = entry.key == "with" || entry.key == "if" || entry.key == keyword;
+        if !is_statement && !seen_keys.insert(entry.key.clone()) {
+            return Err(entry.error_at_key(format!("Duplicate key '{}'", entry.key)));
+        }
+
+        if entry.value.as_deref() == Some(indicator) {
+            entry.block = Some(collect_block(lines, pos, indent, line_prefix));
+        } else if *pos < lines.len() && lines[*pos].indent > indent {
+            entry.children = parse_entries(lines, pos, indicator, line_prefix, keyword, &mut entry.comments)?;
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Parse a single `key: value`, `with name = value`, `if condition:` or `<keyword> ...` statement line
+fn parse_entry_line(line: &SourceLine<'_>, keyword: &str) -> Result<Entry, ScriptError> {
+    let text = line.text.trim_end();
+    let column = line.indent + 1;
+
+    // `if <condition>:` opens a block of conditional entries, keyed "if"
+    if let Some(condition) = text.strip_prefix("if ").and_then(|rest| rest.strip_suffix(':')) {
+        let value = condition.trim();
+        return Ok(Entry {
+            key: "if".to_string(),
+            value: if value.is_empty() { None } else { Some(value.to_string()) },
+            line: line.number,
+            key_column: column,
+            value_column: column + "if ".len() + condition.len() - condition.trim_start().len(),
+            children: Vec::new(),
+            block: None,
+            comments: Vec::new(),
+        });
+    }
+
+    // Keys may start with the assignment keyword too (an action named `set x`),
+    // so only lines that are not `key: value` pairs are assignments
+    let is_key_line = text.ends_with(':') || text.contains(": ");
+    let statement = if text.starts_with("with ") {
+        Some("with")
+    } else if text.strip_prefix(keyword).is_some_and(|rest| rest.starts_with(' ')) && !is_key_line {
+        Some(keyword)
+    } else {
+        None
+    };
+    let (key, value_offset) = if let Some(word) = statement {
+        // Clauses keep everything after the keyword as their value
+        (word.to_string(), word.len() + 1)
+    } else if text.starts_with('"') {
+        let (key, consumed) = parse_quoted(text, line.number, column)?;
+        if !text[consumed..].starts_with(':') {
+            return Err(ScriptError::new(
+                line.number,
+                column + text[..consumed].chars().count(),
+                "Expected ':' after quoted key",
+            ));
+        }
+        (key, consumed + 1)
+    } else {
+        let colon = match text.find(": ") {
+            Some(index) => index,
+            None if text.ends_with(':') => text.len() - 1,
+            None => {
+                return Err(ScriptError::new(line.number, column, "Expected 'key: value'"));
+            }
+        };
+        (text[..colon].trim_end().to_string(), colon + 1)
+    };
+
+    let after = &text[value_offset..];
+    let value = after.trim_start();
+    let value_column = column + text[..text.len() - value.len()].chars().count();
+
+    Ok(Entry {
+        key,
+        value: if value.is_empty() { None } else { Some(value.to_string()) },
+        line: line.number,
+        key_column: column,
+        value_column,
+        children: Vec::new(),
+        block: None,
+        comments: Vec::new(),
+    })
+}
+
+/// Collect the lines of an instructions block nested deeper than `parent_indent`
+/// Lines starting with the line prefix open a new instruction line; other lines
+/// are continuations and keep their indentation relative to the first line's text
+fn collect_block(
+    lines: &[SourceLine<'_>],
+    pos: &mut usize,
+    parent_indent: usize,
+    line_prefix: &str,
+) -> Vec<String> {
+    let mut block = Vec::new();
+    let mut text_indent: Option<usize> = None;
+
+    while *pos < lines.len() && lines[*pos].indent > parent_indent {
+        let line = &lines[*pos];
+        *pos += 1;
+
+        if let Some(rest) = line.text.strip_prefix(line_prefix) {
+            let content = rest.strip_prefix(' ').unwrap_or(rest);
+            if text_indent.is_none() {
+                text_indent = Some(line.indent + line.text.len() - content.len());
+            }
+            block.push(content.to_string());
+        } else {
+            let base = *text_indent.get_or_insert(line.indent);
+            let extra = line.indent.saturating_sub(base);
+            block.push(format!("{}{}", " ".repeat(extra), line.text));
+        }
+    }
+
+    block
+}
+
+/// Parse a double-quoted string at the start of `text`
+/// Returns the unescaped string and the number of bytes consumed
+fn parse_quoted(text: &str, line: usize, column: usize) -> Result<(String, usize), ScriptError> {
+    let mut result = String::new();
+    let mut chars = text.char_indices().skip(1);
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((result, index + 1)),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => result.push('\n'),
+                Some((_, 'r')) => result.push('\r'),
+                Some((_, 't')) => result.push('\t'),
+                Some((_, '"')) => result.push('"'),
+                Some((_, '\\')) => result.push('\\'),
+                Some((escape_index, other)) => {
+                    return Err(ScriptError::new(
+                        line,
+                        column + text[..escape_index].chars().count() - 1,
+                        format!("Unknown escape sequence '\\{}'", other),
+                    ));
+                }
+                None => break,
+            },
+            _ => result.push(c),
+        }
+    }
+
+    Err(ScriptError::new(line, column, "Unterminated string"))
+}
+
+// ============================================================================
+// VALUE HELPERS
+// ============================================================================
+
+/// Read an entry's value as a string, unquoting it if needed
+/// Scalar entries cannot have nested entries
+fn scalar(entry: &Entry) -> Result<String, ScriptError> {
+    if let Some(child) = entry.children.first() {
+        return Err(ScriptError::new(child.line, child.key_column, "Unexpected indentation"));
+    }
+    value_text(entry)
+}
+
+/// Unquote an entry's value, ignoring any nested entries
+fn value_text(entry: &Entry) -> Result<String, ScriptError> {
+    let value = match &entry.value {
+        Some(value) => value,
+        None => return Ok(String::new()),
+    };
+
+    if !value.starts_with('"') {
+        return Ok(value.clone());
+    }
+
+    let (text, consumed) = parse_quoted(value, entry.line, entry.value_column)?;
+    if !value[consumed..].trim().is_empty() {
+        return Err(ScriptError::new(
+            entry.line,
+            entry.value_column + value[..consumed].chars().count(),
+            "Unexpected text after closing quote",
+        ));
+    }
+    Ok(text)
+}
+
+/// Read an entry's value as a required string
+/// An explicitly quoted empty string (`""`) counts as a value
+fn required_scalar(entry: &Entry, what: &str) -> Result<String, ScriptError> {
+    if entry.value.is_none() {
+        return Err(entry.error_at_key(format!("'{}' is missing {}", entry.key, what)));
+    }
+    value_text(entry)
+}
+
+/// Read an entry's value as a boolean
+fn boolean(entry: &Entry, rules: Option<&ConversionRules>) -> Result<bool, ScriptError> {
+    let value = scalar(entry)?;
+    parse_boolean(&value, rules)
+        .ok_or_else(|| entry.error_at_value(format!("Expected a boolean for '{}', found '{}'", entry.key, value)))
+}
+
+/// Read a boolean token
+/// Accepts true/false in any case and every boolean format from the rules
+fn parse_boolean(value: &str, rules: Option<&ConversionRules>) -> Option<bool> {
+    let mut true_values = vec![YAML_TRUE.to_string()];
+    let mut false_values = vec![YAML_FALSE.to_string()];
+    for block in [BooleanBlock::Language, BooleanBlock::Knowledge, BooleanBlock::Connection, BooleanBlock::Actions] {
+        let (true_val, false_val) = get_boolean_format(rules, block);
+        true_values.push(true_val);
+        false_values.push(false_val);
+    }
+
+    if true_values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+        Some(true)
+    } else if false_values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Ensure an entry opens a nested block rather than holding a value
+fn expect_block(entry: &Entry) -> Result<(), ScriptError> {
+    if entry.value.is_some() {
+        return Err(entry.error_at_value(format!("'{}' must be a nested block", entry.key)));
+    }
+    Ok(())
+}
+
+/// Build the error for a key that is not allowed in `context`
+fn unknown_field(entry: &Entry, context: &str) -> ScriptError {
+    entry.error_at_key(format!("Unknown field '{}' in {}", entry.key, context))
+}
+
+// ============================================================================
+// SECTION PARSERS
+// ============================================================================
+
+/// Parse the `system:` block
+fn parse_system(entry: &Entry, system: &mut SystemSection) -> Result<(), ScriptError> {
+    expect_block(entry)?;
+    for child in &entry.children {
+        match child.key.as_str() {
+            "instructions" => system.instructions = block_or_scalar(child)?,
+            "messages" => {
+                expect_block(child)?;
+                for message in &child.children {
+                    match message.key.as_str() {
+                        "welcome" => system.messages.welcome = scalar(message)?,
+                        "error" => system.messages.error = scalar(message)?,
+                        _ => return Err(unknown_field(message, "system messages")),
+                    }
+                }
+            }
+            _ => return Err(unknown_field(child, "system")),
+        }
+    }
+    Ok(())
+}
+
+/// Parse the `config:` block
+fn parse_config(entry: &Entry, config: &mut ConfigSection) -> Result<(), ScriptError> {
+    expect_block(entry)?;
+    for child in &entry.children {
+        let value = scalar(child)?;
+        match child.key.as_str() {
+            "default_agent_user" => config.default_agent_user = value,
+            "agent_label" => config.agent_label = value,
+            "developer_name" => config.developer_name = value,
+            "agent_type" => config.agent_type = value,
+            "description" => config.description = value,
+            _ => return Err(unknown_field(child, "config")),
+        }
+    }
+    Ok(())
+}
+
+/// Parse the `variables:` block
+fn parse_variables(entry: &Entry) -> Result<IndexMap<String, Variable>, ScriptError> {
+    expect_block(entry)?;
+    let mut variables = IndexMap::new();
+
+    for child in &entry.children {
+        let mut variable = Variable {
+            var_type: required_scalar(child, "a type")?,
+            label: None,
+            source: None,
+            description: String::new(),
+        };
+
+        for field in &child.children {
+            match field.key.as_str() {
+                "source" => variable.source = Some(scalar(field)?),
+                "label" => variable.label = Some(scalar(field)?),
+                "description" => variable.description = scalar(field)?,
+                _ => return Err(unknown_field(field, "variable")),
+            }
+        }
+
+        variables.insert(child.key.clone(), variable);
+    }
+
+    Ok(variables)
+}
+
+/// Parse the `language:` block
+fn parse_language(
+    entry: &Entry,
+    language: &mut LanguageSection,
+    rules: Option<&ConversionRules>,
+) -> Result<(), ScriptError> {
+    expect_block(entry)?;
+    for child in &entry.children {
+        match child.key.as_str() {
+            "default_locale" => language.default_locale = scalar(child)?,
+            "additional_locales" => language.additional_locales = scalar(child)?,
+            "all_additional_locales" => language.all_additional_locales = boolean(child, rules)?,
+            _ => return Err(unknown_field(child, "language")),
+        }
+    }
+    Ok(())
+}
+
+/// Parse the `knowledge:` block
+fn parse_knowledge(
+    entry: &Entry,
+    knowledge: &mut KnowledgeSection,
+    rules: Option<&ConversionRules>,
+) -> Result<(), ScriptError> {
+    expect_block(entry)?;
+    for child in &entry.children {
+        match child.key.as_str() {
+            "rag_feature_config_id" => knowledge.rag_feature_config_id = scalar(child)?,
+            "citations_enabled" => knowledge.citations_enabled = boolean(child, rules)?,
+            _ => return Err(unknown_field(child, "knowledge")),
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `connection <name>:` block
+fn parse_connection(
+    entry: &Entry,
+    rules: Option<&ConversionRules>,
+) -> Result<ConnectionSection, ScriptError> {
+    expect_block(entry)?;
+    let mut connection = ConnectionSection { adaptive_response_allowed: false };
+    for child in &entry.children {
+        match child.key.as_str() {
+            "adaptive_response_allowed" => connection.adaptive_response_allowed = boolean(child, rules)?,
+            _ => return Err(unknown_field(child, "connection")),
+        }
+    }
+    Ok(connection)
+}
+
+/// Parse a `topic <name>:` or `start_agent <name>:` block
+fn parse_topic(entry: &Entry, rules: Option<&ConversionRules>) -> Result<Topic, ScriptError> {
+    expect_block(entry)?;
+    let mut topic = Topic {
+        label: String::new(),
+        description: String::new(),
+        classification_description: None,
+        reasoning: ReasoningSection {
+            instructions: String::new(),
+            actions: None,
+        },
+        actions: None,
+    };
+
+    let mut bound_values = Vec::new();
+    for child in &entry.children {
+        match child.key.as_str() {
+            "label" => topic.label = scalar(child)?,
+            "description" => topic.description = scalar(child)?,
+            "classification_description" => topic.classification_description = Some(scalar(child)?),
+            "reasoning" => topic.reasoning = parse_reasoning(child, rules, &mut bound_values)?,
+            "actions" => {
+                expect_block(child)?;
+                let mut actions = IndexMap::new();
+                for action in &child.children {
+                    actions.insert(action.key.clone(), parse_action(action, rules)?);
+                }
+                topic.actions = Some(actions);
+            }
+            _ => return Err(unknown_field(child, "topic")),
+        }
+    }
+
+    // Literal `with` values are the const values of the referenced action's inputs
+    for (target, param, value) in bound_values {
+        let input = target.strip_prefix("@actions.")
+            .and_then(|name| topic.actions.as_mut()?.get_mut(name))
+            .and_then(|action| action.inputs.as_mut()?.get_mut(&param));
+        if let Some(input) = input {
+            input.const_value = Some(value);
+        }
+    }
+
+    Ok(topic)
+}
+
+/// A literal `with` value: (reasoning action target, parameter, value)
+type BoundValue = (String, String, serde_json::Value);
+
+/// Parse a topic's `reasoning:` block
+fn parse_reasoning(
+    entry: &Entry,
+    rules: Option<&ConversionRules>,
+    bound_values: &mut Vec<BoundValue>,
+) -> Result<ReasoningSection, ScriptError> {
+    expect_block(entry)?;
+    let mut reasoning = ReasoningSection {
+        instructions: String::new(),
+        actions: None,
+    };
+
+    for child in &entry.children {
+        match child.key.as_str() {
+            "instructions" => reasoning.instructions = block_or_scalar(child)?,
+            "actions" => {
+                expect_block(child)?;
+                let mut actions = IndexMap::new();
+                for action in &child.children {
+                    if action.key != "if" {
+                        insert_reasoning_action(&mut actions, action, None, rules, bound_values)?;
+                        continue;
+                    }
+                    // Actions inside `if <condition>:` are only available when the condition holds
+                    let condition = action
+                        .value
+                        .clone()
+                        .ok_or_else(|| action.error_at_key("'if' is missing a condition"))?;
+                    for conditional in &action.children {
+                        insert_reasoning_action(&mut actions, conditional, Some(&condition), rules, bound_values)?;
+                    }
+                }
+                reasoning.actions = Some(actions);
+            }
+            _ => return Err(unknown_field(child, "reasoning")),
+        }
+    }
+
+    Ok(reasoning)
+}
+
+/// Parse a reasoning action and add it under its key
+/// Keys must be unique across the plain actions and every `if` block
+fn insert_reasoning_action(
+    actions: &mut IndexMap<String, ReasoningAction>,
+    entry: &Entry,
+    condition: Option<&str>,
+    rules: Option<&ConversionRules>,
+    bound_values: &mut Vec<BoundValue>,
+) -> Result<(), ScriptError> {
+    if actions.contains_key(&entry.key) {
+        return Err(entry.error_at_key(format!("Duplicate key '{}'", entry.key)));
+    }
+    let mut action = parse_reasoning_action(entry, rules, bound_values)?;
+    action.condition = condition.map(str::to_string);
+    actions.insert(entry.key.clone(), action);
+    Ok(())
+}
+
+/// Parse a reasoning action reference with its `with` clauses
+fn parse_reasoning_action(
+    entry: &Entry,
+    rules: Option<&ConversionRules>,
+    bound_values: &mut Vec<BoundValue>,
+) -> Result<ReasoningAction, ScriptError> {
+    let mut action = ReasoningAction {
+        target: required_scalar(entry, "a target")?,
+        description: None,
+        with_params: None,
+        set_clauses: None,
+        condition: None,
+        comments: if entry.comments.is_empty() { None } else { Some(entry.comments.clone()) },
+    };
+
+    let assignment_syntax = get_assignment_syntax(rules);
+    let keyword = assignment_keyword(&assignment_syntax);
+    let mut params = Vec::new();
+    let mut set_clauses = Vec::new();
+    for child in &entry.children {
+        match child.key.as_str() {
+            "with" => {
+                let param = with_param(child, rules)?;
+                if let WithBinding::Literal(value) = &param.binding {
+                    bound_values.push((action.target.clone(), param.name.clone(), value.clone()));
+                }
+                params.push(param);
+            }
+            "description" => action.description = Some(scalar(child)?),
+            key if key == keyword => set_clauses.push(set_clause(child, &assignment_syntax)?),
+            _ => return Err(unknown_field(child, "reasoning action")),
+        }
+    }
+
+    if !params.is_empty() {
+        action.with_params = Some(params);
+    }
+    if !set_clauses.is_empty() {
+        action.set_clauses = Some(set_clauses);
+    }
+    Ok(action)
+}
+
+/// Read a `with <name> = <binding>` clause
+/// Names containing `=` or surrounding whitespace are double-quoted
+fn with_param(entry: &Entry, rules: Option<&ConversionRules>) -> Result<WithParam, ScriptError> {
+    let clause = entry.value.as_deref().unwrap_or_default();
+    let (name, rest) = if clause.starts_with('"') {
+        let (name, consumed) = parse_quoted(clause, entry.line, entry.value_column)?;
+        (name, &clause[consumed..])
+    } else {
+        let name_end = clause.find('=').unwrap_or(clause.len());
+        let name = clause[..name_end].trim();
+        if name.is_empty() {
+            return Err(entry.error_at_value("Expected 'with <name> = <value>'"));
+        }
+        (name.to_string(), &clause[name_end..])
+    };
+
+    let value = match rest.trim_start().strip_prefix('=') {
+        Some(value) => value.trim(),
+        None => return Err(entry.error_at_value("Expected 'with <name> = <value>'")),
+    };
+    let binding = with_binding(entry, &name, value, rules)?;
+    Ok(WithParam { name, binding })
+}
+
+/// Read the value of a `with` clause: `...`, `@variables.<name>` or a
+/// string, boolean or number literal
+fn with_binding(entry: &Entry, name: &str, value: &str, rules: Option<&ConversionRules>) -> Result<WithBinding, ScriptError> {
+    if value == SLOT_FILL {
+        return Ok(WithBinding::SlotFill);
+    }
+    if let Some(variable) = value.strip_prefix(VARIABLE_REFERENCE_PREFIX) {
+        if !variable.is_empty() {
+            return Ok(WithBinding::Variable(variable.to_string()));
+        }
+    }
+    if value.starts_with('"') {
+        let column = entry.value_column + entry.value.as_deref().unwrap_or_default().chars().count()
+            - value.chars().count();
+        let (text, consumed) = parse_quoted(value, entry.line, column)?;
+        if !value[consumed..].trim().is_empty() {
+            return Err(ScriptError::new(entry.line, column + value[..consumed].chars().count(), "Unexpected text after closing quote"));
+        }
+        return Ok(WithBinding::Literal(serde_json::Value::String(text)));
+    }
+    if let Some(b) = parse_boolean(value, rules) {
+        return Ok(WithBinding::Literal(serde_json::Value::Bool(b)));
+    }
+    if let Ok(number) = serde_json::from_str::<serde_json::Number>(value) {
+        return Ok(WithBinding::Literal(serde_json::Value::Number(number)));
+    }
+    Err(entry.error_at_value(format!(
+        "Expected '...', '@variables.<name>' or a literal for 'with {}', found '{}'",
+        name, value
+    )))
+}
+
+/// Read a `set @variables.<variable> = @outputs.<output>` clause written with the assignment syntax
+fn set_clause(entry: &Entry, syntax: &str) -> Result<SetClause, ScriptError> {
+    let pattern = format!(
+        "^{}$",
+        regex::escape(syntax)
+            .replace(r"\{variable\}", &format!(r"{}(?P<variable>\S+)", regex::escape(VARIABLE_REFERENCE_PREFIX)))
+            .replace(r"\{value\}", &format!(r"{}(?P<output>\S+)", regex::escape(OUTPUT_REFERENCE_PREFIX)))
+    );
+    let statement = format!("{} {}", entry.key, entry.value.as_deref().unwrap_or_default());
+    regex::Regex::new(&pattern)
+        .ok()
+        .and_then(|re| re.captures(&statement))
+        .map(|captures| SetClause {
+            variable: captures["variable"].to_string(),
+            output: captures["output"].to_string(),
+        })
+        .ok_or_else(|| entry.error_at_key(format!(
+            "Expected '{}' with @variables.<name> and @outputs.<name>, found '{}'",
+            syntax, statement
+        )))
+}
+
+/// Parse a detailed action definition
+fn parse_action(entry: &Entry, rules: Option<&ConversionRules>) -> Result<Action, ScriptError> {
+    expect_block(entry)?;
+    let mut action = Action {
+        description: String::new(),
+        label: None,
+        require_user_confirmation: false,
+        include_in_progress_indicator: false,
+        progress_indicator_message: None,
+        source: None,
+        target: String::new(),
+        inputs: None,
+        outputs: None,
+    };
+
+    for child in &entry.children {
+        match child.key.as_str() {
+            "description" => action.description = scalar(child)?,
+            "label" => action.label = Some(scalar(child)?),
+            "require_user_confirmation" => action.require_user_confirmation = boolean(child, rules)?,
+            "include_in_progress_indicator" => action.include_in_progress_indicator = boolean(child, rules)?,
+            "progress_indicator_message" => action.progress_indicator_message = Some(scalar(child)?),
+            "source" => action.source = Some(scalar(child)?),
+            "target" => action.target = scalar(child)?,
+            "inputs" => {
+                expect_block(child)?;
+                let mut inputs = IndexMap::new();
+                for input in &child.children {
+                    inputs.insert(input.key.clone(), parse_action_input(input, rules)?);
+                }
+                action.inputs = Some(inputs);
+            }
+            "outputs" => {
+                expect_block(child)?;
+                let mut outputs = IndexMap::new();
+                for output in &child.children {
+                    outputs.insert(output.key.clone(), parse_action_output(output, rules)?);
+                }
+                action.outputs = Some(outputs);
+            }
+            _ => return Err(unknown_field(child, "action")),
+        }
+    }
+
+    Ok(action)
+}
+
+/// Parse an action input definition
+fn parse_action_input(entry: &Entry, rules: Option<&ConversionRules>) -> Result<ActionInputDef, ScriptError> {
+    let mut input = ActionInputDef {
+        input_type: required_scalar(entry, "a type")?,
+        const_value: None,
+        description: None,
+        label: None,
+        is_required: false,
+        is_user_input: false,
+        complex_data_type_name: None,
+    };
+
+    for child in &entry.children {
+        match child.key.as_str() {
+            "description" => input.description = Some(scalar(child)?),
+            "label" => input.label = Some(scalar(child)?),
+            "is_required" => input.is_required = boolean(child, rules)?,
+            "is_user_input" => input.is_user_input = boolean(child, rules)?,
+            "complex_data_type_name" => input.complex_data_type_name = Some(scalar(child)?),
+            _ => return Err(unknown_field(child, "action input")),
+        }
+    }
+
+    Ok(input)
+}
+
+/// Parse an action output definition
+fn parse_action_output(entry: &Entry, rules: Option<&ConversionRules>) -> Result<ActionOutputDef, ScriptError> {
+    let mut output = ActionOutputDef {
+        output_type: required_scalar(entry, "a type")?,
+        description: None,
+        label: None,
+        is_displayable: false,
+        is_used_by_planner: false,
+        complex_data_type_name: None,
+    };
+
+    for child in &entry.children {
+        match child.key.as_str() {
+            "description" => output.description = Some(scalar(child)?),
+            "label" => output.label = Some(scalar(child)?),
+            "is_displayable" => output.is_displayable = boolean(child, rules)?,
+            "is_used_by_planner" => output.is_used_by_planner = boolean(child, rules)?,
+            "complex_data_type_name" => output.complex_data_type_name = Some(scalar(child)?),
+            _ => return Err(unknown_field(child, "action output")),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Read instructions given either as an indicator block or as a single string
+fn block_or_scalar(entry: &Entry) -> Result<String, ScriptError> {
+    match &entry.block {
+        Some(lines) => Ok(lines.join("\n")),
+        None => scalar(entry),
+    }
+}
+
+// ============================================================================
+// PUBLIC API
+// ============================================================================
+
+/// Parse NGA Agent Script text (as produced by `generate_nga_yaml`) into an NGAOutput
+///
+/// Sections missing from the script get the same defaults the converter uses.
+/// The instructions indicator, line prefix, assignment syntax and boolean format are read from the rules.
+pub fn parse_agent_script(
+    script: &str,
+    rules: Option<&ConversionRules>,
+) -> Result<NGAOutput, ScriptError> {
+    let lines = collect_lines(script)?;
+    if lines.is_empty() {
+        return Err(ScriptError::new(1, 1, "Script is empty"));
+    }
+    if lines[0].indent != 0 {
+        return Err(ScriptError::new(lines[0].number, lines[0].indent + 1, "Unexpected indentation"));
+    }
+
+    let indicator = get_instruction_indicator(rules);
+    let line_prefix = get_instruction_line_prefix(rules);
+    let assignment_syntax = get_assignment_syntax(rules);
+    let mut pos = 0;
+    let keyword = assignment_keyword(&assignment_syntax);
+    let entries = parse_entries(&lines, &mut pos, &indicator, &line_prefix, keyword, &mut Vec::new())?;
+
+    let (instructions, welcome, error) = get_default_system_values();
+    let (default_locale, all_additional_locales) = get_default_language_values();
+    let mut nga = NGAOutput {
+        system: SystemSection {
+            instructions,
+            messages: MessagesSection { welcome, error },
+        },
+        config: ConfigSection {
+            default_agent_user: String::new(),
+            agent_label: String::new(),
+            developer_name: String::new(),
+            agent_type: String::new(),
+            description: String::new(),
+        },
+        topics: IndexMap::new(),
+        variables: IndexMap::new(),
+        language: LanguageSection {
+            default_locale,
+            additional_locales: String::new(),
+            all_additional_locales,
+        },
+        knowledge: KnowledgeSection {
+            rag_feature_config_id: String::new(),
+            citations_enabled: false,
+        },
+        connections: IndexMap::new(),
+    };
+
+    for entry in &entries {
+        match entry.key.as_str() {
+            "system" => parse_system(entry, &mut nga.system)?,
+            "config" => parse_config(entry, &mut nga.config)?,
+            "variables" => nga.variables = parse_variables(entry)?,
+            "language" => parse_language(entry, &mut nga.language, rules)?,
+            "knowledge" => parse_knowledge(entry, &mut nga.knowledge, rules)?,
+            key if key.starts_with("connection ") => {
+                nga.connections.insert(key.to_string(), parse_connection(entry, rules)?);
+            }
+            key if key.starts_with("topic ") || key.starts_with("start_agent ") => {
+                nga.topics.insert(key.to_string(), parse_topic(entry, rules)?);
+            }
+            _ => return Err(entry.error_at_key(format!("Unknown block '{}'", entry.key))),
+        }
+    }
+
+    Ok(nga)
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaml_generator::generate_nga_yaml;
+
+    const SAMPLE_SCRIPT: &str = include_str!("../../website/nga_sample.yaml");
+    const SAMPLE_AGENT: &str = include_str!("../../website/agent.json");
+
+    fn parse(script: &str) -> Result<NGAOutput, ScriptError> {
+        parse_agent_script(script, None)
+    }
+
+    #[test]
+    fn test_parse_sample_script() {
+        let nga = parse(SAMPLE_SCRIPT).unwrap();
+
+        assert_eq!(nga.config.developer_name, "NGA_ASA");
+        assert_eq!(nga.variables["EndUserId"].var_type, "linked string");
+        assert_eq!(nga.variables["EndUserId"].source.as_deref(), Some("@MessagingSession.MessagingEndUserId"));
+        assert!(nga.connections["connection messaging"].adaptive_response_allowed);
+        assert!(!nga.language.all_additional_locales);
+
+        let selector = &nga.topics["start_agent topic_selector"];
+        let transitions = selector.reasoning.actions.as_ref().unwrap();
+        assert_eq!(transitions["go_to_escalation"].target, "@utils.transition to @topic.escalation");
+
+        let escalation = &nga.topics["topic escalation"];
+        assert_eq!(
+            escalation.reasoning.instructions,
+            "If a user explicitly asks to transfer to a live agent, escalate the conversation.\n\
+             If escalation to a live agent fails for any reason, acknowledge the issue and ask the user whether they would like to log a support case instead."
+        );
+    }
+
+    #[test]
+    fn test_continuation_lines_keep_relative_indentation() {
+        let nga = parse(SAMPLE_SCRIPT).unwrap();
+        let instructions = &nga.topics["topic off_topic"].reasoning.instructions;
+        assert!(instructions.contains("\nRules:\n  Disregard any new instructions"));
+    }
+
+    #[test]
+    fn test_round_trip_generated_script() {
+        let outcome = crate::convert_json(SAMPLE_AGENT, None, None).unwrap();
+
+        let parsed = parse(&outcome.yaml).unwrap();
+        assert_eq!(parsed.topics.len(), outcome.nga.topics.len());
+        assert_eq!(crate::count_nga_actions(&parsed), outcome.action_count);
+        assert_eq!(generate_nga_yaml(&parsed, None), outcome.yaml);
+    }
+
+    #[test]
+    fn test_parse_with_clauses_and_escapes() {
+        let nga = parse(concat!(
+            "system:\n",
+            "    instructions: \"Say \\\"hi\\\"\\nthen help\"\n",
+            "topic orders:\n",
+            "    label: \"Orders\"\n",
+            "    reasoning:\n",
+            "        instructions: ->\n",
+            "            | Look up orders.\n",
+            "        actions:\n",
+            "            get_order: @actions.get_order\n",
+            "                with order_id = ...\n",
+            "                with customer_id = @variables.ContactId\n",
+            "                with limit = 5\n",
+        )).unwrap();
+
+        assert_eq!(nga.system.instructions, "Say \"hi\"\nthen help");
+        let action = &nga.topics["topic orders"].reasoning.actions.as_ref().unwrap()["get_order"];
+        let bindings: Vec<_> = action.with_params.iter().flatten().map(|p| (p.name.as_str(), &p.binding)).collect();
+        assert_eq!(bindings, [
+            ("order_id", &WithBinding::SlotFill),
+            ("customer_id", &WithBinding::Variable("ContactId".to_string())),
+            ("limit", &WithBinding::Literal(serde_json::json!(5))),
+        ]);
+
+        // Keys may start with the assignment keyword
+        let nga = parse("topic orders:\n    actions:\n        set status:\n            target: \"flow://Set_Status\"\n").unwrap();
+        assert!(nga.topics["topic orders"].actions.as_ref().unwrap().contains_key("set status"));
+
+        let error = parse("topic orders:\n    reasoning:\n        actions:\n            a: @actions.a\n                with id = @outputs.id\n").unwrap_err();
+        assert_eq!(error.line, 5);
+        assert!(error.message.contains("'with id'"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_unknown_field_reports_position() {
+        let err = parse("config:\n  agent_label: \"A\"\n  colour: \"blue\"\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+        assert!(err.message.contains("colour"));
+    }
+
+    #[test]
+    fn test_bad_indentation_reports_position() {
+        let err = parse("system:\n    instructions: \"A\"\n      messages:\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 7));
+    }
+
+    #[test]
+    fn test_unterminated_string_reports_position() {
+        let err = parse("config:\n  agent_label: \"Support\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 16));
+        assert_eq!(err.message, "Unterminated string");
+    }
+
+    #[test]
+    fn test_invalid_boolean_reports_value_position() {
+        let err = parse("language:\n    all_additional_locales: maybe\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 29));
+    }
+
+    #[test]
+    fn test_duplicate_topic_is_error() {
+        let err = parse("topic a:\n    label: \"A\"\ntopic a:\n    label: \"B\"\n").unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+}
@@ -0,0 +1,233 @@
+//! Writer for the NGA Agent Script dialect
+//!
+//! Every key and scalar goes through the writer, which quotes and escapes it
+//! whenever writing it bare would not read back verbatim through
+//! `script_parser::parse_agent_script`.
+
+use crate::models::*;
+use crate::helpers::*;
+use crate::yaml_generator::{get_indentation, get_instruction_indicator, get_instruction_line_prefix};
+
+// ============================================================================
+// QUOTING
+// ============================================================================
+
+/// Quote and escape a string
+pub fn quote(s: &str) -> String {
+    format!("\"{}\"", escape_yaml_string(s))
+}
+
+/// Format a key, quoting it unless it reads back verbatim when written bare
+pub fn key(name: &str) -> String {
+    if is_plain(name) && !name.contains(':') && !name.starts_with("with ") && !name.starts_with("if ") {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+/// Whether a string can be written without quotes: it starts with a letter,
+/// digit, `_` or `@`, has no surrounding whitespace and no control characters
+fn is_plain(s: &str) -> bool {
+    match s.chars().next() {
+        Some(first) if first.is_alphanumeric() || first == '_' || first == '@' => {}
+        _ => return false,
+    }
+    s.trim() == s && !s.chars().any(char::is_control)
+}
+
+// ============================================================================
+// SCRIPT WRITER
+// ============================================================================
+
+/// Builds Agent Script text line by line
+///
+/// Lines are placed by nesting depth: top-level blocks at depth 0, their
+/// fields at depth 1 (`base` spaces) and each deeper level `nested` spaces further.
+pub struct ScriptWriter {
+    output: String,
+    base_indent: usize,
+    nested_indent: usize,
+    indicator: String,
+    line_prefix: String,
+}
+
+impl ScriptWriter {
+    /// Create a writer using the indentation, instructions indicator and line prefix from the rules
+    pub fn new(rules: Option<&ConversionRules>) -> Self {
+        let (base_indent, nested_indent) = get_indentation(rules);
+        ScriptWriter {
+            output: String::new(),
+            base_indent,
+            nested_indent,
+            indicator: get_instruction_indicator(rules),
+            line_prefix: get_instruction_line_prefix(rules),
+        }
+    }
+
+    /// Write `key:` opening a nested block
+    pub fn block(&mut self, depth: usize, key: &str) {
+        self.line(depth, &format!("{}:", key));
+    }
+
+    /// Write `key: "value"`, always quoted
+    pub fn string(&mut self, depth: usize, key: &str, value: &str) {
+        self.entry(depth, key, &quote(value));
+    }
+
+    /// Write `key: value`, bare when the value reads back verbatim (types,
+    /// targets, sources), quoted otherwise
+    pub fn plain(&mut self, depth: usize, key: &str, value: &str) {
+        if is_plain(value) && value != self.indicator {
+            self.entry(depth, key, value);
+        } else {
+            self.string(depth, key, value);
+        }
+    }
+
+    /// Write `key: value` with a literal token such as a formatted boolean
+    pub fn literal(&mut self, depth: usize, key: &str, value: &str) {
+        self.entry(depth, key, value);
+    }
+
+    /// Write a `with <param> = <value>` clause, quoting the parameter name if needed
+    /// `value` is written as given (`...` to let the planner fill the slot)
+    pub fn with_clause(&mut self, depth: usize, param: &str, value: &str) {
+        let name = if is_plain(param) && !param.contains('=') {
+            param.to_string()
+        } else {
+            quote(param)
+        };
+        self.line(depth, &format!("with {} = {}", name, value));
+    }
+
+    /// Write a statement line such as a `set` clause, as given
+    pub fn statement(&mut self, depth: usize, text: &str) {
+        self.line(depth, text);
+    }
+
+    /// Write `# text` comment lines, one per line of text
+    pub fn comment(&mut self, depth: usize, text: &str) {
+        for line in text.lines() {
+            self.line(depth, format!("# {}", line).trim_end());
+        }
+    }
+
+    /// Write an instructions block (`key: ->` followed by prefixed lines)
+    ///
+    /// Text with carriage returns or other control characters (tabs aside)
+    /// cannot be carried by block lines and is written as a quoted string instead.
+    pub fn instructions(&mut self, depth: usize, key: &str, text: &str) {
+        let representable = !text.is_empty()
+            && !text.chars().any(|c| c.is_control() && c != '\n' && c != '\t');
+        if !representable {
+            self.string(depth, key, text);
+            return;
+        }
+
+        let indicator = self.indicator.clone();
+        self.entry(depth, key, &indicator);
+        let prefix = self.line_prefix.clone();
+        for line in text.split('\n') {
+            self.line(depth + 1, &format!("{} {}", prefix, line));
+        }
+    }
+
+    /// Write an empty line separating sections (never more than one in a row)
+    pub fn blank_line(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    /// Finish the script, with exactly one trailing newline
+    pub fn finish(self) -> String {
+        format!("{}\n", self.output.trim_end_matches('\n'))
+    }
+
+    /// Write `key: value` with the value as given
+    fn entry(&mut self, depth: usize, key: &str, value: &str) {
+        self.line(depth, &format!("{}: {}", key, value));
+    }
+
+    /// Write one line at the given depth
+    fn line(&mut self, depth: usize, text: &str) {
+        let indent = match depth {
+            0 => 0,
+            _ => self.base_indent + (depth - 1) * self.nested_indent,
+        };
+        self.output.push_str(&" ".repeat(indent));
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_quoting() {
+        assert_eq!(key("topic orders"), "topic orders");
+        assert_eq!(key("go_to_orders"), "go_to_orders");
+        assert_eq!(key("a: b"), "\"a: b\"");
+        assert_eq!(key("with x"), "\"with x\"");
+        assert_eq!(key("if x"), "\"if x\"");
+        assert_eq!(key(" padded"), "\" padded\"");
+        assert_eq!(key(""), "\"\"");
+    }
+
+    #[test]
+    fn test_plain_values() {
+        let mut writer = ScriptWriter::new(None);
+        writer.plain(0, "a", "@utils.transition to @topic.orders");
+        writer.plain(0, "b", "->");
+        writer.plain(0, "c", "\"quoted\"");
+        writer.plain(0, "d", "");
+        assert_eq!(
+            writer.finish(),
+            "a: @utils.transition to @topic.orders\nb: \"->\"\nc: \"\\\"quoted\\\"\"\nd: \"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_instructions_block() {
+        let mut writer = ScriptWriter::new(None);
+        writer.instructions(2, "instructions", "first\n  second");
+        writer.instructions(2, "instructions", "line\rbreak");
+        assert_eq!(
+            writer.finish(),
+            "        instructions: ->\n            | first\n            |   second\n        instructions: \"line\\rbreak\"\n"
+        );
+    }
+
+    #[test]
+    fn test_depth_and_blank_lines() {
+        let rules = crate::parse_rules(r#"{"output_format": {"indentation": {"base": 2, "nested": 3}}}"#).unwrap();
+        let mut writer = ScriptWriter::new(rules.as_ref());
+        writer.blank_line();
+        writer.block(0, "config");
+        writer.string(1, "agent_label", "Agent");
+        writer.blank_line();
+        writer.blank_line();
+        writer.block(0, "topic orders");
+        writer.block(1, "reasoning");
+        writer.instructions(2, "instructions", "Help");
+        assert_eq!(
+            writer.finish(),
+            "config:\n  agent_label: \"Agent\"\n\ntopic orders:\n  reasoning:\n     instructions: ->\n        | Help\n"
+        );
+    }
+
+    #[test]
+    fn test_with_clause_quotes_names() {
+        let mut writer = ScriptWriter::new(None);
+        writer.with_clause(0, "query", "...");
+        writer.with_clause(0, "a = b", "\"fixed\"");
+        assert_eq!(writer.finish(), "with query = ...\nwith \"a = b\" = \"fixed\"\n");
+    }
+}
@@ -0,0 +1,753 @@
+use std::collections::HashSet;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::models::*;
+use crate::error::path_field;
+use crate::helpers::TRANSITION_PREFIX;
+use crate::yaml_generator::get_required_blocks;
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+/// Config fields required when the rules do not list any
+const DEFAULT_CONFIG_REQUIRED_FIELDS: &[&str] = &["agent_label", "developer_name"];
+
+/// Topic fields required when the rules do not list any
+const DEFAULT_TOPIC_REQUIRED_FIELDS: &[&str] = &["label", "description", "reasoning"];
+
+/// Variable types allowed when the rules do not list any
+const DEFAULT_VARIABLE_TYPES: &[&str] = &[
+    "string", "number", "boolean", "date", "id", "object",
+    "list[string]", "list[number]", "list[boolean]", "list[date]", "list[id]", "list[object]",
+];
+
+/// Variable categories (type prefixes) allowed when the rules do not list any
+const DEFAULT_VARIABLE_CATEGORIES: &[&str] = &["linked", "mutable"];
+
+/// Maximum length of API names (developer name, variable and topic names)
+const DEFAULT_NAME_MAX_LENGTH: usize = 80;
+
+/// Prefix of action references in reasoning actions
+const ACTION_REFERENCE_PREFIX: &str = "@actions.";
+
+// ============================================================================
+// STATIC REGEX PATTERNS
+// ============================================================================
+
+/// Matches @topic.name references in instructions
+static TOPIC_REFERENCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"@topic\.([A-Za-z0-9_]+)").expect("Invalid regex pattern for TOPIC_REFERENCE_RE")
+});
+
+/// Matches @actions.name references in instructions
+static ACTION_REFERENCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"@actions\.([A-Za-z0-9_]+)").expect("Invalid regex pattern for ACTION_REFERENCE_RE")
+});
+
+/// Matches @variables.name references in conditions
+static VARIABLE_REFERENCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"@variables\.([A-Za-z0-9_]+)").expect("Invalid regex pattern for VARIABLE_REFERENCE_RE")
+});
+
+// ============================================================================
+// DIAGNOSTICS
+// ============================================================================
+
+/// Severity of a validation diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The output violates a rule and will not be accepted as-is
+    Error,
+    /// The output is accepted but probably not what was intended
+    Warning,
+}
+
+/// A single validation finding
+///
+/// `path` locates the element in the NGA output using block keys
+/// (e.g. `topic orders.reasoning.actions.go_to_billing`), or the input element
+/// for conversion warnings (e.g. `plugins[0].functions[1].invocationTargetType`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Error, path: path.into(), message: message.into() }
+    }
+
+    pub(crate) fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Warning, path: path.into(), message: message.into() }
+    }
+}
+
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+/// Validate an NGA output against the constraints in the conversion rules
+///
+/// Checks required blocks, config field patterns and lengths, variable names
+/// and types, required topic fields, and runs the cross-reference analysis.
+/// Patterns the regex engine cannot compile (the rules use lookaround for API
+/// names) fall back to the built-in API name checks.
+pub fn validate_nga(nga: &NGAOutput, rules: Option<&ConversionRules>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    validate_blocks(nga, rules, &mut diagnostics);
+    validate_config(nga, rules, &mut diagnostics);
+    validate_variables(nga, rules, &mut diagnostics);
+    validate_topics(nga, rules, &mut diagnostics);
+    diagnostics.extend(check_references(nga));
+
+    diagnostics
+}
+
+/// Check that every required block is present and not empty
+/// `variables` may be empty: an agent needs none, and the writer emits the block when it is required
+fn validate_blocks(nga: &NGAOutput, rules: Option<&ConversionRules>, diagnostics: &mut Vec<Diagnostic>) {
+    for block in &get_required_blocks(rules) {
+        let present = match block.as_str() {
+            "system" => !nga.system.instructions.is_empty(),
+            "config" => !nga.config.developer_name.is_empty() || !nga.config.agent_label.is_empty(),
+            "variables" => true,
+            "language" => !nga.language.default_locale.is_empty(),
+            "knowledge" => !nga.knowledge.rag_feature_config_id.is_empty(),
+            "connection" => nga.connections.keys().any(|k| k.starts_with("connection ")),
+            "start_agent" => nga.topics.keys().any(|k| k.starts_with("start_agent ")),
+            "topic" => nga.topics.keys().any(|k| k.starts_with("topic ")),
+            _ => {
+                diagnostics.push(Diagnostic::warning(
+                    block.as_str(),
+                    format!("Unknown required block '{}' in rules", block),
+                ));
+                continue;
+            }
+        };
+
+        if !present {
+            diagnostics.push(Diagnostic::error(
+                block.as_str(),
+                format!("Required block '{}' is missing or empty", block),
+            ));
+        }
+    }
+}
+
+/// Check required config fields, field patterns and maximum lengths
+fn validate_config(nga: &NGAOutput, rules: Option<&ConversionRules>, diagnostics: &mut Vec<Diagnostic>) {
+    let config_rules = rules.and_then(|r| r.config.as_ref());
+
+    let required = config_rules
+        .and_then(|c| c.required_fields.clone())
+        .unwrap_or_else(|| DEFAULT_CONFIG_REQUIRED_FIELDS.iter().map(|s| s.to_string()).collect());
+    for field in &required {
+        if config_field(&nga.config, field).is_some_and(|value| value.is_empty()) {
+            diagnostics.push(Diagnostic::error(
+                path_field("config", field),
+                format!("Required config field '{}' is empty", field),
+            ));
+        }
+    }
+
+    let fields = config_rules.and_then(|c| c.fields.as_ref());
+    let developer_name_rule = fields.and_then(|f| f.get("developer_name"));
+    if developer_name_rule.is_none() && !nga.config.developer_name.is_empty() {
+        // Built-in API name check when the rules say nothing about the developer name
+        check_api_name(&nga.config.developer_name, "config.developer_name", "Developer name", None, diagnostics);
+    }
+
+    if let Some(fields) = fields {
+        let mut names: Vec<_> = fields.keys().collect();
+        names.sort();
+        for name in names {
+            let rule = &fields[name];
+            let value = match config_field(&nga.config, name) {
+                Some(value) if !value.is_empty() => value,
+                _ => continue,
+            };
+            let path = path_field("config", name);
+
+            if let Some(pattern) = &rule.pattern {
+                match Regex::new(pattern) {
+                    Ok(re) => {
+                        if !re.is_match(value) {
+                            diagnostics.push(Diagnostic::error(
+                                path.as_str(),
+                                format!("Config field '{}' value '{}' does not match pattern '{}'", name, value, pattern),
+                            ));
+                        }
+                    }
+                    Err(_) => check_api_name(value, &path, &format!("Config field '{}'", name), None, diagnostics),
+                }
+            }
+
+            if let Some(max_length) = rule.max_length {
+                check_max_length(value, max_length, &path, &format!("Config field '{}'", name), diagnostics);
+            }
+        }
+    }
+}
+
+/// Maximum length of API names from the naming rules
+fn name_max_length(rules: Option<&ConversionRules>) -> usize {
+    rules
+        .and_then(|r| r.variables.as_ref())
+        .and_then(|v| v.name_rules.as_ref())
+        .and_then(|n| n.max_length)
+        .unwrap_or(DEFAULT_NAME_MAX_LENGTH)
+}
+
+/// Check variable names, types and sources
+fn validate_variables(nga: &NGAOutput, rules: Option<&ConversionRules>, diagnostics: &mut Vec<Diagnostic>) {
+    let variable_rules = rules.and_then(|r| r.variables.as_ref());
+    let name_rules = variable_rules.and_then(|v| v.name_rules.as_ref());
+    let name_pattern = name_rules.and_then(|n| n.pattern.as_ref());
+    let name_regex = name_pattern.and_then(|p| Regex::new(p).ok());
+    let max_length = name_max_length(rules);
+
+    let allowed_types = allowed_variable_types(rules);
+    let categories = variable_rules.and_then(|v| v.categories.as_ref());
+    let category_names: HashSet<String> = match categories {
+        Some(categories) => categories.keys().cloned().collect(),
+        None => DEFAULT_VARIABLE_CATEGORIES.iter().map(|s| s.to_string()).collect(),
+    };
+
+    let mut names: Vec<_> = nga.variables.keys().collect();
+    names.sort();
+    for name in names {
+        let variable = &nga.variables[name];
+        let path = path_field("variables", name);
+
+        // Name: rules pattern when supported, built-in API name rules otherwise
+        match (&name_regex, name_pattern) {
+            (Some(re), Some(pattern)) => {
+                if !re.is_match(name) {
+                    diagnostics.push(Diagnostic::error(
+                        path.as_str(),
+                        format!("Variable name '{}' does not match pattern '{}'", name, pattern),
+                    ));
+                }
+            }
+            _ => check_api_name(name, &path, &format!("Variable name '{}'", name), None, diagnostics),
+        }
+        check_max_length(name, max_length, &path, &format!("Variable name '{}'", name), diagnostics);
+
+        // Type: optional category prefix followed by a known type
+        let mut parts: Vec<&str> = variable.var_type.split_whitespace().collect();
+        let base_type = parts.pop().unwrap_or_default();
+        let category = parts.first().copied();
+        if parts.len() > 1 {
+            diagnostics.push(Diagnostic::error(
+                path.as_str(),
+                format!("Variable '{}' has malformed type '{}'", name, variable.var_type),
+            ));
+            continue;
+        }
+        if let Some(category) = category {
+            if !category_names.contains(category) {
+                diagnostics.push(Diagnostic::error(
+                    path.as_str(),
+                    format!("Variable '{}' has unknown category '{}'", name, category),
+                ));
+            }
+        }
+        if !allowed_types.contains(base_type) {
+            diagnostics.push(Diagnostic::error(
+                path.as_str(),
+                format!("Variable '{}' has unknown type '{}'", name, base_type),
+            ));
+        }
+
+        // Source: linked variables need one, other categories should not have one
+        let has_source = match (category, categories) {
+            (Some(category), Some(categories)) => categories.get(category).and_then(|c| c.has_source),
+            (Some(category), None) => Some(category == "linked"),
+            (None, _) => Some(false),
+        };
+        match (has_source, &variable.source) {
+            (Some(true), None) => diagnostics.push(Diagnostic::error(
+                path_field(&path, "source"),
+                format!("Variable '{}' is {} but has no source", name, category.unwrap_or_default()),
+            )),
+            (Some(false), Some(_)) => diagnostics.push(Diagnostic::warning(
+                path_field(&path, "source"),
+                format!("Variable '{}' has a source but is not linked", name),
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Check topic names, required fields and action targets
+fn validate_topics(nga: &NGAOutput, rules: Option<&ConversionRules>, diagnostics: &mut Vec<Diagnostic>) {
+    let required_fields = rules
+        .and_then(|r| r.topics.as_ref())
+        .and_then(|t| t.required_fields.clone())
+        .unwrap_or_else(|| DEFAULT_TOPIC_REQUIRED_FIELDS.iter().map(|s| s.to_string()).collect());
+    let max_length = name_max_length(rules);
+
+    let mut keys: Vec<_> = nga.topics.keys().collect();
+    keys.sort();
+    for key in keys {
+        let name = match topic_name(key) {
+            Some(name) => name,
+            None => continue,
+        };
+        let topic = &nga.topics[key];
+
+        check_api_name(name, key, &format!("Topic name '{}'", name), Some(max_length), diagnostics);
+
+        for field in &required_fields {
+            let missing = match field.as_str() {
+                "label" => topic.label.is_empty(),
+                "description" => topic.description.is_empty(),
+                "reasoning" => topic.reasoning.instructions.trim().is_empty(),
+                _ => false,
+            };
+            if missing {
+                diagnostics.push(Diagnostic::error(
+                    path_field(key, field),
+                    format!("Topic '{}' is missing required field '{}'", name, field),
+                ));
+            }
+        }
+
+        if let Some(actions) = &topic.actions {
+            let mut action_keys: Vec<_> = actions.keys().collect();
+            action_keys.sort();
+            for action_name in action_keys {
+                if actions[action_name].target.trim().is_empty() {
+                    diagnostics.push(Diagnostic::error(
+                        path_field(&path_field(&path_field(key, "actions"), action_name), "target"),
+                        format!("Action '{}' has no target", action_name),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// CROSS-REFERENCE ANALYSIS
+// ============================================================================
+
+/// Check references between topics and actions
+///
+/// Flags transitions to undefined topics, `@actions.` references to actions
+/// the topic does not define, `with` and `set` clauses naming undefined
+/// variables or outputs, and topics no other topic transitions to.
+/// Mentions in instructions text are reported as warnings only.
+pub fn check_references(nga: &NGAOutput) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let topic_names: HashSet<&str> = nga.topics.keys().filter_map(|key| topic_name(key)).collect();
+    let mut transition_targets: HashSet<&str> = HashSet::new();
+
+    let mut keys: Vec<_> = nga.topics.keys().collect();
+    keys.sort();
+    for key in &keys {
+        let name = match topic_name(key) {
+            Some(name) => name,
+            None => continue,
+        };
+        let topic = &nga.topics[*key];
+
+        let action_names: HashSet<&str> = topic
+            .actions
+            .as_ref()
+            .map(|actions| actions.keys().map(|k| k.as_str()).collect())
+            .unwrap_or_default();
+
+        // Reasoning action targets must point at existing topics and actions
+        if let Some(reasoning_actions) = &topic.reasoning.actions {
+            let mut action_keys: Vec<_> = reasoning_actions.keys().collect();
+            action_keys.sort();
+            for action_key in action_keys {
+                let reasoning_action = &reasoning_actions[action_key];
+                let target = reasoning_action.target.trim();
+                let path = path_field(&path_field(&path_field(key, "reasoning"), "actions"), action_key);
+
+                if let Some(target_topic) = target.strip_prefix(TRANSITION_PREFIX) {
+                    if target_topic != name {
+                        transition_targets.insert(target_topic);
+                    }
+                    if !topic_names.contains(target_topic) {
+                        diagnostics.push(Diagnostic::error(
+                            path.as_str(),
+                            format!("Transition '{}' targets undefined topic '{}'", action_key, target_topic),
+                        ));
+                    }
+                } else if let Some(target_action) = target.strip_prefix(ACTION_REFERENCE_PREFIX) {
+                    if !action_names.contains(target_action) {
+                        diagnostics.push(Diagnostic::error(
+                            path.as_str(),
+                            format!("Reasoning action '{}' references undefined action '{}'", action_key, target_action),
+                        ));
+                    }
+                } else if !target.starts_with("@utils.") {
+                    diagnostics.push(Diagnostic::warning(
+                        path.as_str(),
+                        format!("Reasoning action '{}' has unrecognized target '{}'", action_key, target),
+                    ));
+                }
+
+                // `if` conditions may only compare defined variables
+                for captures in VARIABLE_REFERENCE_RE.captures_iter(reasoning_action.condition.as_deref().unwrap_or_default()) {
+                    if !nga.variables.contains_key(&captures[1]) {
+                        diagnostics.push(Diagnostic::error(
+                            path.as_str(),
+                            format!(
+                                "Reasoning action '{}' is conditioned on undefined variable '@variables.{}'",
+                                action_key, &captures[1]
+                            ),
+                        ));
+                    }
+                }
+
+                // `with` clauses may only bind defined variables
+                for param in reasoning_action.with_params.iter().flatten() {
+                    if let WithBinding::Variable(variable) = &param.binding {
+                        if !nga.variables.contains_key(variable) {
+                            diagnostics.push(Diagnostic::error(
+                                path.as_str(),
+                                format!(
+                                    "Reasoning action '{}' binds '{}' to undefined variable '@variables.{}'",
+                                    action_key, param.name, variable
+                                ),
+                            ));
+                        }
+                    }
+                }
+
+                // `set` clauses store outputs of the referenced action in defined variables
+                let outputs = target.strip_prefix(ACTION_REFERENCE_PREFIX)
+                    .and_then(|action| topic.actions.as_ref()?.get(action))
+                    .map(|action| action.outputs.as_ref());
+                for clause in reasoning_action.set_clauses.iter().flatten() {
+                    if !nga.variables.contains_key(&clause.variable) {
+                        diagnostics.push(Diagnostic::error(
+                            path.as_str(),
+                            format!(
+                                "Reasoning action '{}' sets undefined variable '@variables.{}'",
+                                action_key, clause.variable
+                            ),
+                        ));
+                    }
+                    if let Some(outputs) = outputs {
+                        if !outputs.is_some_and(|outputs| outputs.contains_key(&clause.output)) {
+                            diagnostics.push(Diagnostic::warning(
+                                path.as_str(),
+                                format!(
+                                    "Reasoning action '{}' reads undefined output '@outputs.{}'",
+                                    action_key, clause.output
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        // References in the instructions text
+        let instructions_path = path_field(&path_field(key, "reasoning"), "instructions");
+        let instructions = &topic.reasoning.instructions;
+        for captures in TOPIC_REFERENCE_RE.captures_iter(instructions) {
+            if !topic_names.contains(&captures[1]) {
+                diagnostics.push(Diagnostic::warning(
+                    instructions_path.as_str(),
+                    format!("Instructions reference undefined topic '@topic.{}'", &captures[1]),
+                ));
+            }
+        }
+        for captures in ACTION_REFERENCE_RE.captures_iter(instructions) {
+            if !action_names.contains(&captures[1]) {
+                diagnostics.push(Diagnostic::warning(
+                    instructions_path.as_str(),
+                    format!("Instructions reference undefined action '@actions.{}'", &captures[1]),
+                ));
+            }
+        }
+    }
+
+    // Topics other than the entry point need at least one inbound transition
+    for key in &keys {
+        if let Some(name) = key.strip_prefix("topic ") {
+            if !transition_targets.contains(name) {
+                diagnostics.push(Diagnostic::warning(
+                    key.as_str(),
+                    format!("Topic '{}' is unreachable: no transition targets it", name),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+// ============================================================================
+// CHECK HELPERS
+// ============================================================================
+
+/// Get a config field value by its NGA name
+fn config_field<'a>(config: &'a ConfigSection, name: &str) -> Option<&'a str> {
+    match name {
+        "default_agent_user" => Some(&config.default_agent_user),
+        "agent_label" => Some(&config.agent_label),
+        "developer_name" => Some(&config.developer_name),
+        "agent_type" => Some(&config.agent_type),
+        "description" => Some(&config.description),
+        _ => None,
+    }
+}
+
+/// Get the name of a `topic <name>` or `start_agent <name>` key
+fn topic_name(key: &str) -> Option<&str> {
+    key.strip_prefix("topic ").or_else(|| key.strip_prefix("start_agent "))
+}
+
+/// Collect the variable types allowed by the rules
+fn allowed_variable_types(rules: Option<&ConversionRules>) -> HashSet<String> {
+    if let Some(rules) = rules {
+        if let Some(variables) = &rules.variables {
+            if let Some(types) = &variables.types {
+                return [&types.primitive, &types.complex, &types.list]
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .cloned()
+                    .collect();
+            }
+        }
+    }
+    DEFAULT_VARIABLE_TYPES.iter().map(|s| s.to_string()).collect()
+}
+
+/// Built-in API name rules: begins with a letter, only alphanumerics and
+/// underscores, no trailing or consecutive underscores
+fn check_api_name(
+    value: &str,
+    path: &str,
+    what: &str,
+    max_length: Option<usize>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let problem = if value.is_empty() {
+        Some("must not be empty")
+    } else if !value.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        Some("must begin with a letter")
+    } else if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some("can contain only alphanumeric characters and underscores")
+    } else if value.ends_with('_') {
+        Some("cannot end with an underscore")
+    } else if value.contains("__") {
+        Some("cannot contain consecutive underscores")
+    } else {
+        None
+    };
+
+    if let Some(problem) = problem {
+        diagnostics.push(Diagnostic::error(path, format!("{} {}", what, problem)));
+    }
+    if let Some(max_length) = max_length {
+        check_max_length(value, max_length, path, what, diagnostics);
+    }
+}
+
+/// Check a value against a maximum length in characters
+fn check_max_length(value: &str, max_length: usize, path: &str, what: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let length = value.chars().count();
+    if length > max_length {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!("{} is {} characters long (maximum {})", what, length, max_length),
+        ));
+    }
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RULES: &str = include_str!("../../website/nga-rules.json");
+    const SAMPLE_SCRIPT: &str = include_str!("../../website/nga_sample.yaml");
+
+    fn rules() -> Option<ConversionRules> {
+        crate::parse_rules(SAMPLE_RULES).unwrap()
+    }
+
+    fn script(text: &str) -> NGAOutput {
+        crate::script_parser::parse_agent_script(text, None).unwrap()
+    }
+
+    fn errors(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+        diagnostics.iter().filter(|d| d.severity == Severity::Error).collect()
+    }
+
+    #[test]
+    fn test_sample_script_is_valid() {
+        let diagnostics = validate_nga(&script(SAMPLE_SCRIPT), rules().as_ref());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_rules_deserialize_validation_sections() {
+        let rules = rules().unwrap();
+        assert!(rules.blocks.unwrap().required.unwrap().contains(&"start_agent".to_string()));
+        assert_eq!(rules.config.unwrap().fields.unwrap()["developer_name"].max_length, Some(80));
+        assert!(rules.variables.unwrap().types.unwrap().list.unwrap().contains(&"list[id]".to_string()));
+        assert!(rules.topics.unwrap().required_fields.unwrap().contains(&"reasoning".to_string()));
+    }
+
+    #[test]
+    fn test_missing_required_blocks() {
+        let nga = script("system:\n    instructions: \"Help\"\nconfig:\n  developer_name: \"Agent\"\n  agent_label: \"Agent\"\n  description: \"An agent\"\n");
+        let diagnostics = validate_nga(&nga, rules().as_ref());
+        let paths: Vec<_> = errors(&diagnostics).iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["start_agent"]);
+    }
+
+    #[test]
+    fn test_invalid_names_use_built_in_rules() {
+        let nga = script(concat!(
+            "config:\n",
+            "  developer_name: \"My__Agent\"\n",
+            "variables:\n",
+            "    order_: mutable string\n",
+            "    9lives: string\n",
+        ));
+        let diagnostics = validate_nga(&nga, rules().as_ref());
+
+        let messages: Vec<_> = diagnostics.iter().map(|d| (d.path.as_str(), d.message.as_str())).collect();
+        assert!(messages.contains(&("config.developer_name", "Config field 'developer_name' cannot contain consecutive underscores")));
+        assert!(messages.contains(&("variables.order_", "Variable name 'order_' cannot end with an underscore")));
+        assert!(messages.contains(&("variables.9lives", "Variable name '9lives' must begin with a letter")));
+    }
+
+    #[test]
+    fn test_length_and_type_checks() {
+        let long_name = format!("a{}", "b".repeat(80));
+        let nga = script(&format!(
+            "config:\n  agent_label: \"A\"\n  developer_name: \"{}\"\nvariables:\n    count: mutable integer\n    owner: linked string\n    note: weird string\n",
+            long_name
+        ));
+        let diagnostics = validate_nga(&nga, rules().as_ref());
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+
+        assert!(messages.contains(&"Config field 'developer_name' is 81 characters long (maximum 80)"));
+        assert!(messages.contains(&"Variable 'count' has unknown type 'integer'"));
+        assert!(messages.contains(&"Variable 'owner' is linked but has no source"));
+        assert!(messages.contains(&"Variable 'note' has unknown category 'weird'"));
+    }
+
+    #[test]
+    fn test_topic_name_length_from_rules() {
+        let nga = script("topic order_status:\n    label: \"Orders\"\n    description: \"Orders\"\n    reasoning:\n        instructions: ->\n            | Help\n");
+        let rules = crate::parse_rules(r#"{"variables": {"name_rules": {"max_length": 10}}}"#).unwrap();
+
+        let topic_messages = |rules: Option<&ConversionRules>| -> Vec<String> {
+            validate_nga(&nga, rules)
+                .into_iter()
+                .filter(|d| d.path == "topic order_status" && d.severity == Severity::Error)
+                .map(|d| d.message)
+                .collect()
+        };
+        assert_eq!(topic_messages(rules.as_ref()), ["Topic name 'order_status' is 12 characters long (maximum 10)"]);
+        assert!(topic_messages(None).is_empty());
+    }
+
+    #[test]
+    fn test_dangling_references() {
+        let nga = script(concat!(
+            "start_agent topic_selector:\n",
+            "    label: \"Topic Selector\"\n",
+            "    description: \"Routes\"\n",
+            "    reasoning:\n",
+            "        instructions: ->\n",
+            "            | Route, or use @topic.billing for invoices.\n",
+            "        actions:\n",
+            "            go_to_orders: @utils.transition to @topic.orders\n",
+            "            go_to_billing: @utils.transition to @topic.billing\n",
+            "topic orders:\n",
+            "    label: \"Orders\"\n",
+            "    description: \"Orders\"\n",
+            "    reasoning:\n",
+            "        instructions: ->\n",
+            "            | Use @actions.get_order.\n",
+            "        actions:\n",
+            "            get_order: @actions.get_order\n",
+            "                with customer_id = @variables.ContactId\n",
+            "                set @variables.Status = @outputs.status\n",
+            "            cancel_order: @actions.cancel_order\n",
+            "            if @variables.Verified == True:\n",
+            "                go_to_billing: @utils.transition to @topic.orders\n",
+            "    actions:\n",
+            "        get_order:\n",
+            "            target: \"flow://Get_Order\"\n",
+        ));
+        let diagnostics = validate_nga(&nga, None);
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| !d.path.starts_with("config"))
+            .map(|d| (d.severity, d.path.as_str()))
+            .collect();
+        assert_eq!(found, [
+            (Severity::Error, "start_agent topic_selector.reasoning.actions.go_to_billing"),
+            (Severity::Warning, "start_agent topic_selector.reasoning.instructions"),
+            (Severity::Error, "topic orders.reasoning.actions.cancel_order"),
+            (Severity::Error, "topic orders.reasoning.actions.get_order"),
+            (Severity::Error, "topic orders.reasoning.actions.get_order"),
+            (Severity::Warning, "topic orders.reasoning.actions.get_order"),
+            (Severity::Error, "topic orders.reasoning.actions.go_to_billing"),
+        ]);
+    }
+
+    #[test]
+    fn test_unreachable_topic() {
+        let nga = script(concat!(
+            "start_agent topic_selector:\n",
+            "    reasoning:\n",
+            "        actions:\n",
+            "            go_to_orders: @utils.transition to @topic.orders\n",
+            "topic orders:\n",
+            "    reasoning:\n",
+            "        actions:\n",
+            "            go_to_orders: @utils.transition to @topic.orders\n",
+            "topic billing:\n",
+            "    label: \"Billing\"\n",
+        ));
+        let diagnostics = check_references(&nga);
+
+        assert_eq!(diagnostics, [Diagnostic::warning(
+            "topic billing",
+            "Topic 'billing' is unreachable: no transition targets it",
+        )]);
+    }
+
+    #[test]
+    fn test_converted_agent_has_no_reference_issues() {
+        let outcome = crate::convert_json(include_str!("../../website/agent.json"), None, None).unwrap();
+        assert!(check_references(&outcome.nga).is_empty());
+    }
+
+    #[test]
+    fn test_converted_agent_is_valid() {
+        let outcome = crate::convert_json(include_str!("../../website/agent.json"), rules().as_ref(), None).unwrap();
+        assert_eq!(validate_nga(&outcome.nga, rules().as_ref()), []);
+
+        // The empty required variables block reads back and still validates
+        assert!(outcome.yaml.contains("\nvariables:\n"));
+        let parsed = crate::parse_agent_script(&outcome.yaml, rules().as_ref()).unwrap();
+        assert_eq!(validate_nga(&parsed, rules().as_ref()), []);
+    }
+}
@@ -42,7 +42,7 @@ static VAR_PATTERN_4: Lazy<Regex> = Lazy::new(|| {
 });
 
 /// Check if input contains variables that need conversion to @variables format
-pub fn check_for_dollar_variables(input: &str, rules: &Option<ConversionRules>) -> bool {
+pub fn check_for_dollar_variables(input: &str, rules: Option<&ConversionRules>) -> bool {
     if let Some(rules) = rules {
         if let Some(var_conv) = &rules.variable_conversion {
             if var_conv.enabled == Some(false) {
@@ -67,7 +67,7 @@ pub fn check_for_dollar_variables(input: &str, rules: &Option<ConversionRules>)
 }
 
 /// Convert variables to @variables format
-pub fn convert_variables_in_text(text: Option<&str>, rules: &Option<ConversionRules>) -> String {
+pub fn convert_variables_in_text(text: Option<&str>, rules: Option<&ConversionRules>) -> String {
     let text = match text {
         Some(t) => t,
         None => return String::new(),
@@ -111,7 +111,7 @@ pub fn convert_variables_in_text(text: Option<&str>, rules: &Option<ConversionRu
 }
 
 /// Get variable alert message from rules
-pub fn get_variable_alert_message(rules: &Option<ConversionRules>) -> String {
+pub fn get_variable_alert_message(rules: Option<&ConversionRules>) -> String {
     if let Some(rules) = rules {
         if let Some(var_conv) = &rules.variable_conversion {
             if let Some(msg) = &var_conv.alert_message {
@@ -123,7 +123,7 @@ pub fn get_variable_alert_message(rules: &Option<ConversionRules>) -> String {
 }
 
 /// Get variable status suffix from rules
-pub fn get_variable_status_suffix(rules: &Option<ConversionRules>) -> String {
+pub fn get_variable_status_suffix(rules: Option<&ConversionRules>) -> String {
     if let Some(rules) = rules {
         if let Some(var_conv) = &rules.variable_conversion {
             if let Some(suffix) = &var_conv.status_suffix {
@@ -144,77 +144,77 @@ mod tests {
 
     #[test]
     fn test_check_for_dollar_variables_with_exclaim_dollar() {
-        assert!(check_for_dollar_variables("{!$MyVar}", &None));
-        assert!(check_for_dollar_variables("text {!$MyVar} more text", &None));
+        assert!(check_for_dollar_variables("{!$MyVar}", None));
+        assert!(check_for_dollar_variables("text {!$MyVar} more text", None));
     }
 
     #[test]
     fn test_check_for_dollar_variables_with_dollar_exclaim() {
-        assert!(check_for_dollar_variables("{$!MyVar}", &None));
+        assert!(check_for_dollar_variables("{$!MyVar}", None));
     }
 
     #[test]
     fn test_check_for_dollar_variables_with_dollar_only() {
-        assert!(check_for_dollar_variables("{$MyVar}", &None));
+        assert!(check_for_dollar_variables("{$MyVar}", None));
     }
 
     #[test]
     fn test_check_for_dollar_variables_without_variables() {
-        assert!(!check_for_dollar_variables("plain text", &None));
-        assert!(!check_for_dollar_variables("no variables here", &None));
+        assert!(!check_for_dollar_variables("plain text", None));
+        assert!(!check_for_dollar_variables("no variables here", None));
     }
 
     #[test]
     fn test_convert_variables_exclaim_dollar() {
-        let result = convert_variables_in_text(Some("{!$MyVar}"), &None);
+        let result = convert_variables_in_text(Some("{!$MyVar}"), None);
         assert_eq!(result, "{!@variables.MyVar}");
     }
 
     #[test]
     fn test_convert_variables_dollar_exclaim() {
-        let result = convert_variables_in_text(Some("{$!MyVar}"), &None);
+        let result = convert_variables_in_text(Some("{$!MyVar}"), None);
         assert_eq!(result, "{!@variables.MyVar}");
     }
 
     #[test]
     fn test_convert_variables_dollar_only() {
-        let result = convert_variables_in_text(Some("{$MyVar}"), &None);
+        let result = convert_variables_in_text(Some("{$MyVar}"), None);
         assert_eq!(result, "{!@variables.MyVar}");
     }
 
     #[test]
     fn test_convert_variables_exclaim_only() {
-        let result = convert_variables_in_text(Some("{!MyVar}"), &None);
+        let result = convert_variables_in_text(Some("{!MyVar}"), None);
         assert_eq!(result, "{!@variables.MyVar}");
     }
 
     #[test]
     fn test_convert_variables_none() {
-        let result = convert_variables_in_text(None, &None);
+        let result = convert_variables_in_text(None, None);
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_convert_variables_no_variables() {
-        let result = convert_variables_in_text(Some("plain text"), &None);
+        let result = convert_variables_in_text(Some("plain text"), None);
         assert_eq!(result, "plain text");
     }
 
     #[test]
     fn test_convert_variables_mixed_text() {
-        let result = convert_variables_in_text(Some("Hello {!$Name}, welcome!"), &None);
+        let result = convert_variables_in_text(Some("Hello {!$Name}, welcome!"), None);
         assert_eq!(result, "Hello {!@variables.Name}, welcome!");
     }
 
     #[test]
     fn test_get_variable_alert_message_default() {
-        let msg = get_variable_alert_message(&None);
+        let msg = get_variable_alert_message(None);
         assert_eq!(msg, DEFAULT_VARIABLE_ALERT_MESSAGE);
     }
 
     #[test]
     fn test_get_variable_status_suffix_default() {
-        let suffix = get_variable_status_suffix(&None);
+        let suffix = get_variable_status_suffix(None);
         assert_eq!(suffix, DEFAULT_VARIABLE_STATUS_SUFFIX);
     }
 }
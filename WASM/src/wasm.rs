@@ -0,0 +1,228 @@
+//! WebAssembly bindings for the website
+//!
+//! Thin wrappers that parse JSON strings, call the crate's Rust API and
+//! convert results to `JsValue`s.
+
+use wasm_bindgen::prelude::*;
+use crate::models::*;
+use crate::report_generator::ReportMetadata;
+use crate::variable_processor::*;
+use crate::{ConvertError, ScriptError};
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Initialize WASM module with panic hook for better error messages
+#[wasm_bindgen(start)]
+pub fn init() {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Parse rules JSON string into ConversionRules
+/// Returns None if the string is empty or parsing fails
+/// Logs a warning to console if parsing fails (for debugging)
+fn parse_rules(rules_json: &str) -> Option<ConversionRules> {
+    match crate::parse_rules(rules_json) {
+        Ok(rules) => rules,
+        Err(e) => {
+            // Log warning for debugging (don't fail, just use defaults)
+            web_sys::console::warn_1(&format!("{}. Using defaults.", e).into());
+            None
+        }
+    }
+}
+
+/// Convert a ConvertError into a JS object `{ kind, path, message }`
+fn error_to_js(error: ConvertError) -> JsValue {
+    serde_wasm_bindgen::to_value(&error)
+        .unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+}
+
+/// Parse an optional ID map text into an IdMap
+/// Returns None if the text is missing or empty
+fn parse_id_map(id_map_text: Option<String>) -> Result<Option<crate::IdMap>, JsValue> {
+    match id_map_text {
+        Some(text) if !text.trim().is_empty() => crate::parse_id_map(&text).map(Some).map_err(error_to_js),
+        _ => Ok(None),
+    }
+}
+
+/// Convert a ScriptError into a JS object `{ line, column, message }`
+fn script_error_to_js(error: ScriptError) -> JsValue {
+    serde_wasm_bindgen::to_value(&error)
+        .unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+}
+
+// ============================================================================
+// EXPORTS
+// ============================================================================
+
+/// Main conversion function - converts input JSON to NGA YAML
+/// 
+/// # Arguments
+/// * `input_json` - JSON string of the input agent configuration
+/// * `rules_json` - Optional JSON string of conversion rules (can be empty string)
+/// * `id_map_text` - Optional record ID resolution table (JSON map, JSON array of
+///   query records or `id,api_name` CSV); IDs missing from it are left in place
+/// 
+/// # Returns
+/// JSON object with:
+/// - `yaml`: The converted YAML string
+/// - `has_variables_with_dollar`: Boolean indicating if variables were converted
+/// - `topic_count`: Number of topics
+/// - `action_count`: Number of actions
+/// - `warnings`: Cross-reference findings as `{ severity, path, message }` objects
+/// - `renames`: Names replaced or suffixed to stay usable and unique, as
+///   `{ path, original, name }` objects
+///
+/// Errors are thrown as `{ kind, path, message }` objects, where `path` is the
+/// JSON path of the offending element (e.g. `plugins[3].functions[1].inputType`)
+#[wasm_bindgen]
+pub fn convert_agent(input_json: &str, rules_json: &str, id_map_text: Option<String>) -> Result<JsValue, JsValue> {
+    let rules = parse_rules(rules_json);
+    let id_map = parse_id_map(id_map_text)?;
+    
+    let outcome = crate::convert_json(input_json, rules.as_ref(), id_map.as_ref())
+        .map_err(error_to_js)?;
+    
+    // Create result object
+    let result = serde_json::json!({
+        "yaml": outcome.yaml,
+        "has_variables_with_dollar": outcome.has_variables_with_dollar,
+        "topic_count": outcome.topic_count,
+        "action_count": outcome.action_count,
+        "alert_message": outcome.alert_message,
+        "status_suffix": outcome.status_suffix,
+        "warnings": outcome.warnings,
+        "renames": outcome.renames
+    });
+    
+    // Convert to JsValue
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Check if input contains variables with $ sign
+#[wasm_bindgen]
+pub fn check_dollar_variables(input: &str, rules_json: &str) -> bool {
+    let rules = parse_rules(rules_json);
+    check_for_dollar_variables(input, rules.as_ref())
+}
+
+/// Get variable alert message
+#[wasm_bindgen]
+pub fn get_alert_message(rules_json: &str) -> String {
+    let rules = parse_rules(rules_json);
+    get_variable_alert_message(rules.as_ref())
+}
+
+/// Get variable status suffix
+#[wasm_bindgen]
+pub fn get_status_suffix(rules_json: &str) -> String {
+    let rules = parse_rules(rules_json);
+    get_variable_status_suffix(rules.as_ref())
+}
+
+/// Parse NGA Agent Script text back into NGA JSON
+///
+/// The returned JSON string can be passed to `count_topics` / `count_actions`.
+/// Errors are thrown as `{ line, column, message }` objects.
+#[wasm_bindgen]
+pub fn parse_agent_script(script: &str, rules_json: &str) -> Result<String, JsValue> {
+    let rules = parse_rules(rules_json);
+
+    let nga = crate::parse_agent_script(script, rules.as_ref())
+        .map_err(script_error_to_js)?;
+
+    serde_json::to_string(&nga)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize NGA JSON: {}", e)))
+}
+
+/// Validate NGA Agent Script text against the conversion rules
+///
+/// # Returns
+/// Array of `{ severity, path, message }` diagnostics (empty when the script is valid)
+///
+/// Syntax errors are thrown as `{ line, column, message }` objects, like `parse_agent_script`
+#[wasm_bindgen]
+pub fn validate_agent_script(script: &str, rules_json: &str) -> Result<JsValue, JsValue> {
+    let rules = parse_rules(rules_json);
+
+    let nga = crate::parse_agent_script(script, rules.as_ref())
+        .map_err(script_error_to_js)?;
+    let diagnostics = crate::validate_nga(&nga, rules.as_ref());
+
+    serde_wasm_bindgen::to_value(&diagnostics)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize diagnostics: {}", e)))
+}
+
+/// Export NGA JSON back to Agentforce JSON (plugins with TOPIC type and functions)
+#[wasm_bindgen]
+pub fn export_agentforce(nga_json: &str, rules_json: &str) -> Result<String, JsValue> {
+    let rules = parse_rules(rules_json);
+    let nga: NGAOutput = serde_json::from_str(nga_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse NGA JSON: {}", e)))?;
+
+    let agent = crate::export_agentforce(&nga, rules.as_ref());
+
+    serde_json::to_string_pretty(&agent)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize Agentforce JSON: {}", e)))
+}
+
+/// Count topics in NGA output (for testing/debugging)
+#[wasm_bindgen]
+pub fn count_topics(nga_json: &str) -> Result<usize, JsValue> {
+    let nga: NGAOutput = serde_json::from_str(nga_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse NGA JSON: {}", e)))?;
+    
+    Ok(nga.topics.len())
+}
+
+/// Count actions in NGA output (for testing/debugging)
+#[wasm_bindgen]
+pub fn count_actions(nga_json: &str) -> Result<usize, JsValue> {
+    let nga: NGAOutput = serde_json::from_str(nga_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse NGA JSON: {}", e)))?;
+    
+    Ok(crate::count_nga_actions(&nga))
+}
+
+/// Generate conversion report data (IP protected)
+/// 
+/// # Arguments
+/// * `input_json` - JSON string of the input agent configuration
+/// * `output_yaml` - The converted YAML string
+/// * `metadata_json` - JSON string with conversion metadata
+/// * `id_map_text` - Optional record ID resolution table passed to `convert_agent`
+/// 
+/// # Returns
+/// JSON object with structured report data (not markdown)
+#[wasm_bindgen]
+pub fn generate_report_data(
+    input_json: &str,
+    output_yaml: &str,
+    metadata_json: &str,
+    id_map_text: Option<String>,
+) -> Result<JsValue, JsValue> {
+    // Parse input JSON
+    let input = crate::parse_input_json(input_json)
+        .map_err(error_to_js)?;
+    
+    // Parse metadata JSON
+    let metadata: ReportMetadata = serde_json::from_str(metadata_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse metadata JSON: {}", e)))?;
+    let id_map = parse_id_map(id_map_text)?;
+    
+    // Generate report data (IP protected logic)
+    let report_data = crate::generate_report(&input, output_yaml, &metadata, id_map.as_ref());
+    
+    // Convert to JsValue
+    serde_wasm_bindgen::to_value(&report_data)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize report data: {}", e)))
+}
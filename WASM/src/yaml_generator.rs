@@ -1,171 +1,476 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use crate::models::*;
 use crate::helpers::*;
 use crate::variable_processor::*;
+use crate::salesforce_id::is_salesforce_id;
+use crate::script_writer::{key, quote, ScriptWriter};
 
 /// Generate NGA YAML output string
-pub fn generate_nga_yaml(nga: &NGAOutput, rules: &Option<ConversionRules>) -> String {
-    let mut output = String::new();
+///
+/// Top-level blocks follow `blocks.order` from the rules; blocks the order does not
+/// mention come last in the default order. Empty blocks are omitted unless required.
+pub fn generate_nga_yaml(nga: &NGAOutput, rules: Option<&ConversionRules>) -> String {
+    let mut writer = ScriptWriter::new(rules);
+    let required = get_required_blocks(rules);
     
-    // System section - apply variable conversion
-    output.push_str("system:\n");
+    for block in get_block_order(rules) {
+        if is_block_empty(nga, &block) && !required.contains(&block) {
+            continue;
+        }
+        match block.as_str() {
+            "system" => write_system(&mut writer, nga, rules),
+            "config" => write_config(&mut writer, nga, rules),
+            "variables" => write_variables(&mut writer, nga, rules),
+            "language" => write_language(&mut writer, nga, rules),
+            "knowledge" => write_knowledge(&mut writer, nga, rules),
+            "connection" => write_connection(&mut writer, nga, rules),
+            "start_agent" | "topic" => write_topics(&mut writer, nga, &block, rules),
+            _ => {}
+        }
+    }
+    
+    writer.finish()
+}
+
+/// Check whether a top-level block has nothing to write
+/// A block is empty when it only holds the values `parse_agent_script` assumes
+/// for a missing block, so omitting it loses nothing
+fn is_block_empty(nga: &NGAOutput, block: &str) -> bool {
+    match block {
+        "system" => {
+            nga.system.instructions == DEFAULT_SYSTEM_INSTRUCTIONS
+                && nga.system.messages.welcome == DEFAULT_WELCOME_MESSAGE
+                && nga.system.messages.error == DEFAULT_ERROR_MESSAGE
+        }
+        "config" => {
+            nga.config.default_agent_user.is_empty()
+                && nga.config.agent_label.is_empty()
+                && nga.config.developer_name.is_empty()
+                && nga.config.agent_type.is_empty()
+                && nga.config.description.is_empty()
+        }
+        "variables" => nga.variables.is_empty(),
+        "language" => {
+            nga.language.default_locale == DEFAULT_LOCALE
+                && nga.language.additional_locales.is_empty()
+                && nga.language.all_additional_locales == DEFAULT_ALL_ADDITIONAL_LOCALES
+        }
+        "knowledge" => nga.knowledge.rag_feature_config_id.is_empty() && !nga.knowledge.citations_enabled,
+        "connection" => !nga.connections.keys().any(|k| k.starts_with("connection ")),
+        "start_agent" | "topic" => {
+            let prefix = format!("{} ", block);
+            !nga.topics.keys().any(|k| k.starts_with(&prefix))
+        }
+        _ => true,
+    }
+}
+
+/// Write the `system:` block - apply variable conversion
+fn write_system(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    writer.block(0, "system");
     let sys_instructions = convert_variables_in_text(Some(&nga.system.instructions), rules);
-    output.push_str(&format!("    instructions: \"{}\"\n", escape_yaml_string(&sys_instructions)));
-    output.push_str("    messages:\n");
+    writer.string(1, "instructions", &sys_instructions);
+    writer.block(1, "messages");
     let welcome_msg = convert_variables_in_text(Some(&nga.system.messages.welcome), rules);
     let error_msg = convert_variables_in_text(Some(&nga.system.messages.error), rules);
-    output.push_str(&format!("        welcome: \"{}\"\n", escape_yaml_string(&welcome_msg)));
-    output.push_str(&format!("        error: \"{}\"\n", escape_yaml_string(&error_msg)));
-    output.push('\n');
-    
-    // Config section
-    output.push_str("config:\n");
-    output.push_str(&format!("  default_agent_user: \"{}\"\n", nga.config.default_agent_user));
-    output.push_str(&format!("  agent_label: \"{}\"\n", nga.config.agent_label));
-    output.push_str(&format!("  developer_name: \"{}\"\n", nga.config.developer_name));
-    output.push_str(&format!("  agent_type: \"{}\"\n", nga.config.agent_type));
+    writer.string(2, "welcome", &welcome_msg);
+    writer.string(2, "error", &error_msg);
+    writer.blank_line();
+}
+
+/// Write the `config:` block
+fn write_config(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    writer.block(0, "config");
+    writer.string(1, "default_agent_user", &nga.config.default_agent_user);
+    writer.string(1, "agent_label", &nga.config.agent_label);
+    writer.string(1, "developer_name", &nga.config.developer_name);
+    writer.string(1, "agent_type", &nga.config.agent_type);
     let config_desc = convert_variables_in_text(Some(&nga.config.description), rules);
-    output.push_str(&format!("  description: \"{}\"\n", escape_yaml_string(&config_desc)));
-    output.push('\n');
+    writer.string(1, "description", &config_desc);
+    writer.blank_line();
+}
+
+/// Write the `variables:` block
+fn write_variables(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    writer.block(0, "variables");
+    for name in ordered_keys(&nga.variables, get_output_order(rules)) {
+        let variable = &nga.variables[name];
+        writer.plain(1, &key(name), &variable.var_type);
+        // Only output source for linked type variables with non-action sources
+        // (e.g., @MessagingSession.*, @User.* but NOT @action.*)
+        if variable.var_type.starts_with("linked") {
+            if let Some(source) = &variable.source {
+                if !source.starts_with("@action.") {
+                    writer.plain(2, "source", source);
+                }
+            }
+        }
+        // Output label if present
+        if let Some(label) = &variable.label {
+            writer.string(2, "label", label);
+        }
+        let var_desc = convert_variables_in_text(Some(&variable.description), rules);
+        writer.string(2, "description", &var_desc);
+    }
+    writer.blank_line();
+}
+
+/// Write the `language:` block
+fn write_language(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    writer.block(0, "language");
+    writer.string(1, "default_locale", &nga.language.default_locale);
+    writer.string(1, "additional_locales", &nga.language.additional_locales);
+    let language_booleans = get_boolean_format(rules, BooleanBlock::Language);
+    writer.literal(1, "all_additional_locales", format_boolean(nga.language.all_additional_locales, &language_booleans));
+    writer.blank_line();
+}
+
+/// Write the `knowledge:` block
+fn write_knowledge(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    writer.block(0, "knowledge");
+    writer.string(1, "rag_feature_config_id", &nga.knowledge.rag_feature_config_id);
+    let knowledge_booleans = get_boolean_format(rules, BooleanBlock::Knowledge);
+    writer.literal(1, "citations_enabled", format_boolean(nga.knowledge.citations_enabled, &knowledge_booleans));
+    writer.blank_line();
+}
+
+/// Write every `connection <name>:` block, in output order
+fn write_connection(writer: &mut ScriptWriter, nga: &NGAOutput, rules: Option<&ConversionRules>) {
+    let connection_booleans = get_boolean_format(rules, BooleanBlock::Connection);
+    let conn_keys = ordered_keys(&nga.connections, get_output_order(rules));
+    for conn_key in conn_keys.into_iter().filter(|k| k.starts_with("connection ")) {
+        let connection = &nga.connections[conn_key];
+        writer.block(0, &key(conn_key));
+        writer.literal(1, "adaptive_response_allowed", format_boolean(connection.adaptive_response_allowed, &connection_booleans));
+        writer.blank_line();
+    }
+}
+
+/// Write the `start_agent <name>:` or `topic <name>:` blocks, in the order from the rules
+fn write_topics(writer: &mut ScriptWriter, nga: &NGAOutput, block: &str, rules: Option<&ConversionRules>) {
+    let prefix = format!("{} ", block);
+    let order = get_output_order(rules);
+    let topic_keys = ordered_keys(&nga.topics, get_topic_order(rules));
     
-    // Variables section
-    if !nga.variables.is_empty() {
-        output.push_str("variables:\n");
-        let mut var_keys: Vec<_> = nga.variables.keys().collect();
-        var_keys.sort();
-        for name in var_keys {
-            let variable = &nga.variables[name];
-            output.push_str(&format!("    {}: {}\n", name, variable.var_type));
-            // Only output source for linked type variables with non-action sources
-            // (e.g., @MessagingSession.*, @User.* but NOT @action.*)
-            if variable.var_type.starts_with("linked") {
-                if let Some(source) = &variable.source {
-                    if !source.starts_with("@action.") {
-                        output.push_str(&format!("        source: {}\n", source));
+    for topic_key in topic_keys.into_iter().filter(|k| k.starts_with(&prefix)) {
+        let topic = &nga.topics[topic_key];
+        writer.block(0, &key(topic_key));
+        writer.string(1, "label", &topic.label);
+        writer.blank_line();
+        
+        // Apply variable conversion to topic description
+        let topic_desc = convert_variables_in_text(Some(&topic.description), rules);
+        writer.string(1, "description", &topic_desc);
+        writer.blank_line();
+        
+        if let Some(classification) = &topic.classification_description {
+            writer.string(1, "classification_description", classification);
+            writer.blank_line();
+        }
+        
+        // Reasoning section
+        writer.block(1, "reasoning");
+        write_instructions_block(writer, &topic.reasoning.instructions, rules);
+        
+        // Reasoning actions (action references with 'with' clauses and descriptions)
+        if let Some(reasoning_actions) = &topic.reasoning.actions {
+            if !reasoning_actions.is_empty() {
+                writer.block(2, "actions");
+                let action_names = ordered_keys(reasoning_actions, order);
+                // Unconditional actions first, then one `if` block per distinct condition
+                let mut conditions: Vec<&str> = Vec::new();
+                for action_name in &action_names {
+                    let action = &reasoning_actions[*action_name];
+                    match &action.condition {
+                        None => write_reasoning_action(writer, 3, action_name, action, order, rules),
+                        Some(condition) if !conditions.contains(&condition.as_str()) => conditions.push(condition),
+                        Some(_) => {}
                     }
                 }
+                for condition in conditions {
+                    writer.statement(3, &format!("if {}:", condition));
+                    for action_name in &action_names {
+                        let action = &reasoning_actions[*action_name];
+                        if action.condition.as_deref() == Some(condition) {
+                            write_reasoning_action(writer, 4, action_name, action, order, rules);
+                        }
+                    }
+                }
+                writer.blank_line();
             }
-            // Output label if present
-            if let Some(label) = &variable.label {
-                output.push_str(&format!("        label: \"{}\"\n", label));
+        }
+        
+        // Full Actions section (detailed definitions)
+        if let Some(actions) = &topic.actions {
+            if !actions.is_empty() {
+                writer.blank_line();
+                writer.block(1, "actions");
+                write_detailed_actions(writer, actions, rules);
             }
-            let var_desc = convert_variables_in_text(Some(&variable.description), rules);
-            output.push_str(&format!("        description: \"{}\"\n", escape_yaml_string(&var_desc)));
         }
+        
+        writer.blank_line();
     }
-    output.push('\n');
-    
-    // Language section
-    output.push_str("language:\n");
-    output.push_str(&format!("    default_locale: \"{}\"\n", nga.language.default_locale));
-    output.push_str(&format!("    additional_locales: \"{}\"\n", nga.language.additional_locales));
-    output.push_str(&format!("    all_additional_locales: {}\n", format_boolean_value(nga.language.all_additional_locales)));
-    output.push('\n');
-    
-    // Knowledge section
-    output.push_str("knowledge:\n");
-    output.push_str(&format!("    rag_feature_config_id: \"{}\"\n", nga.knowledge.rag_feature_config_id));
-    output.push_str(&format!("    citations_enabled: {}\n", format_boolean_value(nga.knowledge.citations_enabled)));
-    output.push('\n');
-    
-    // Connection section
-    let mut conn_keys: Vec<_> = nga.connections.keys().collect();
-    conn_keys.sort();
-    for key in conn_keys {
-        if key.starts_with("connection ") {
-            let connection = &nga.connections[key];
-            output.push_str(&format!("{}:\n", key));
-            output.push_str(&format!("    adaptive_response_allowed: {}\n", format_boolean_value(connection.adaptive_response_allowed)));
-            output.push('\n');
-            break;
+}
+
+/// Write a reasoning action reference with its comments, `with` and `set` clauses and description
+fn write_reasoning_action(
+    writer: &mut ScriptWriter,
+    depth: usize,
+    action_name: &str,
+    action: &ReasoningAction,
+    order: OutputOrder,
+    rules: Option<&ConversionRules>,
+) {
+    writer.plain(depth, &key(action_name), &action.target);
+    // Rules that could not be translated stay visible for review
+    for comment in action.comments.iter().flatten() {
+        writer.comment(depth + 1, comment);
+    }
+    // Add 'with' clauses for action parameters
+    if let Some(params) = &action.with_params {
+        let mut params: Vec<_> = params.iter().collect();
+        if order == OutputOrder::Alphabetical {
+            params.sort_by(|a, b| a.name.cmp(&b.name));
         }
+        for param in params {
+            writer.with_clause(depth + 1, &param.name, &format_binding(&param.binding, rules));
+        }
+    }
+    // Add 'set' clauses storing action outputs in variables
+    for clause in action.set_clauses.iter().flatten() {
+        writer.statement(depth + 1, &format_assignment(clause, rules));
+    }
+    // Add description if present
+    if let Some(desc) = &action.description {
+        let desc_converted = convert_variables_in_text(Some(desc), rules);
+        writer.string(depth + 1, "description", &desc_converted);
+    }
+}
+
+/// Write instructions block with proper syntax
+fn write_instructions_block(writer: &mut ScriptWriter, instructions: &str, rules: Option<&ConversionRules>) {
+    if instructions.trim().is_empty() {
+        writer.instructions(2, "instructions", "Handle user requests appropriately.");
+        return;
     }
     
-    // Topics sections
-    let mut topic_keys: Vec<_> = nga.topics.keys().collect();
-    topic_keys.sort();
-    for key in topic_keys {
-        if key.starts_with("start_agent ") || key.starts_with("topic ") {
-            let topic = &nga.topics[key];
-            output.push_str(&format!("{}:\n", key));
-            output.push_str(&format!("    label: \"{}\"\n", topic.label));
-            output.push('\n');
-            
-            // Apply variable conversion to topic description
-            let topic_desc = convert_variables_in_text(Some(&topic.description), rules);
-            output.push_str(&format!("    description: \"{}\"\n", escape_yaml_string(&topic_desc)));
-            output.push('\n');
-            
-            // Reasoning section
-            output.push_str("    reasoning:\n");
-            output.push_str(&format_instructions_block(&topic.reasoning.instructions, rules));
-            
-            // Reasoning actions (action references with 'with' clauses and descriptions)
-            if let Some(reasoning_actions) = &topic.reasoning.actions {
-                if !reasoning_actions.is_empty() {
-                    output.push_str("        actions:\n");
-                    let mut action_keys: Vec<_> = reasoning_actions.keys().collect();
-                    action_keys.sort();
-                    for action_name in action_keys {
-                        let action = &reasoning_actions[action_name];
-                        output.push_str(&format!("            {}: {}\n", action_name, action.target));
-                        // Add 'with' clauses for action parameters
-                        if let Some(params) = &action.with_params {
-                            for param in params {
-                                output.push_str(&format!("                with {} = ...\n", param));
-                            }
-                        }
-                        // Add description if present
-                        if let Some(desc) = &action.description {
-                            let desc_converted = convert_variables_in_text(Some(desc), rules);
-                            output.push_str(&format!("                description: \"{}\"\n", escape_yaml_string(&desc_converted)));
-                        }
+    let converted_instructions = convert_variables_in_text(Some(instructions), rules);
+    let lines: Vec<&str> = converted_instructions.lines().collect();
+    writer.instructions(2, "instructions", &lines.join("\n"));
+}
+
+/// Get the top-level block order from the rules
+/// Known blocks missing from `blocks.order` follow in the default order
+pub fn get_block_order(rules: Option<&ConversionRules>) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    if let Some(rules) = rules {
+        if let Some(blocks) = &rules.blocks {
+            if let Some(rules_order) = &blocks.order {
+                for block in rules_order {
+                    if DEFAULT_BLOCK_ORDER.contains(&block.as_str()) && !order.contains(block) {
+                        order.push(block.clone());
                     }
-                    output.push('\n');
                 }
             }
-            
-            // Full Actions section (detailed definitions)
-            if let Some(actions) = &topic.actions {
-                if !actions.is_empty() {
-                    output.push_str("\n    actions:\n");
-                    output.push_str(&format_detailed_actions(actions, rules));
+        }
+    }
+    for block in DEFAULT_BLOCK_ORDER {
+        if !order.iter().any(|b| b == block) {
+            order.push(block.to_string());
+        }
+    }
+    order
+}
+
+/// Get the blocks that are written even when empty
+pub fn get_required_blocks(rules: Option<&ConversionRules>) -> Vec<String> {
+    if let Some(rules) = rules {
+        if let Some(blocks) = &rules.blocks {
+            if let Some(required) = &blocks.required {
+                return required.clone();
+            }
+        }
+    }
+    DEFAULT_REQUIRED_BLOCKS.iter().map(|s| s.to_string()).collect()
+}
+
+/// Order of topics, actions, parameters and variables in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputOrder {
+    /// Sorted by name (the default)
+    Alphabetical,
+    /// The order entries were added to the NGA model (the order in the input)
+    Source,
+}
+
+/// Parse an order setting, `source` or anything else for alphabetical
+fn parse_output_order(value: Option<&str>) -> Option<OutputOrder> {
+    match value? {
+        "source" => Some(OutputOrder::Source),
+        _ => Some(OutputOrder::Alphabetical),
+    }
+}
+
+/// Get the order of variables, actions, inputs/outputs and `with` parameters
+/// from `output_format.order` (`alphabetical` or `source`)
+pub fn get_output_order(rules: Option<&ConversionRules>) -> OutputOrder {
+    if let Some(rules) = rules {
+        if let Some(output_format) = &rules.output_format {
+            if let Some(order) = parse_output_order(output_format.order.as_deref()) {
+                return order;
+            }
+        }
+    }
+    OutputOrder::Alphabetical
+}
+
+/// Get the topic order from `blocks.topic_order`, falling back to `output_format.order`
+pub fn get_topic_order(rules: Option<&ConversionRules>) -> OutputOrder {
+    if let Some(rules) = rules {
+        if let Some(blocks) = &rules.blocks {
+            if let Some(order) = parse_output_order(blocks.topic_order.as_deref()) {
+                return order;
+            }
+        }
+    }
+    get_output_order(rules)
+}
+
+/// Keys of a map in output order
+fn ordered_keys<V>(map: &IndexMap<String, V>, order: OutputOrder) -> Vec<&String> {
+    let mut keys: Vec<_> = map.keys().collect();
+    if order == OutputOrder::Alphabetical {
+        keys.sort();
+    }
+    keys
+}
+
+/// Blocks whose boolean rendering can be set separately in the rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanBlock {
+    Language,
+    Knowledge,
+    Connection,
+    Actions,
+}
+
+/// Get the (true, false) strings for booleans in a block
+/// Order: the block's own `boolean_format`, then `action_definition.boolean_format`,
+/// then the `YAML_TRUE`/`YAML_FALSE` defaults (each value falls back separately)
+pub fn get_boolean_format(rules: Option<&ConversionRules>, block: BooleanBlock) -> (String, String) {
+    let mut true_val = YAML_TRUE.to_string();
+    let mut false_val = YAML_FALSE.to_string();
+    if let Some(rules) = rules {
+        if let Some(output_format) = &rules.output_format {
+            let block_rules = match block {
+                BooleanBlock::Language => &output_format.language,
+                BooleanBlock::Knowledge => &output_format.knowledge,
+                BooleanBlock::Connection => &output_format.connection,
+                BooleanBlock::Actions => &None,
+            };
+            let formats = [
+                block_rules.as_ref().and_then(|b| b.boolean_format.as_ref()),
+                output_format.action_definition.as_ref().and_then(|a| a.boolean_format.as_ref()),
+            ];
+            // Apply the fallback first so the block override wins
+            for format in formats.iter().rev().flatten() {
+                if let Some(value) = &format.true_val {
+                    true_val = value.clone();
+                }
+                if let Some(value) = &format.false_val {
+                    false_val = value.clone();
                 }
             }
-            
-            output.push('\n');
         }
     }
-    
-    // Trim excess whitespace but ensure trailing newline
-    format!("{}\n", output.trim())
+    (true_val, false_val)
 }
 
-/// Format instructions block with proper syntax
-fn format_instructions_block(instructions: &str, rules: &Option<ConversionRules>) -> String {
-    let indicator = get_instruction_indicator(rules);
-    let line_prefix = get_instruction_line_prefix(rules);
-    
-    if instructions.is_empty() {
-        return format!("        instructions: {}\n            {} Handle user requests appropriately.\n", indicator, line_prefix);
+/// Format a boolean with the (true, false) strings from `get_boolean_format`
+fn format_boolean(value: bool, format: &(String, String)) -> &str {
+    if value { &format.0 } else { &format.1 }
+}
+
+/// Format a `with` binding: a literal, `@variables.<name>` or `...`
+/// Literals without an Agent Script form fall back to `...`
+pub fn format_binding(binding: &WithBinding, rules: Option<&ConversionRules>) -> String {
+    match binding {
+        WithBinding::Literal(value) => format_literal(value, rules).unwrap_or_else(|| SLOT_FILL.to_string()),
+        WithBinding::Variable(name) => format!("{}{}", VARIABLE_REFERENCE_PREFIX, name),
+        WithBinding::SlotFill => SLOT_FILL.to_string(),
     }
-    
-    let converted_instructions = convert_variables_in_text(Some(instructions), rules);
-    let lines: Vec<&str> = converted_instructions.lines().collect();
-    
-    if lines.len() == 1 && lines[0].len() < 100 {
-        return format!("        instructions: {}\n            {} {}\n", indicator, line_prefix, lines[0]);
+}
+
+/// Get the post-action assignment syntax from `expressions.assignment.syntax`
+/// The syntax must start with a keyword and contain both `{variable}` and
+/// `{value}`; anything else falls back to `set {variable} = {value}`
+pub fn get_assignment_syntax(rules: Option<&ConversionRules>) -> String {
+    if let Some(rules) = rules {
+        if let Some(expressions) = &rules.expressions {
+            if let Some(assignment) = &expressions.assignment {
+                if let Some(syntax) = &assignment.syntax {
+                    let keyword = assignment_keyword(syntax);
+                    if !keyword.is_empty() && !keyword.contains('{')
+                        && syntax.contains("{variable}") && syntax.contains("{value}")
+                    {
+                        return syntax.clone();
+                    }
+                }
+            }
+        }
     }
-    
-    let mut output = format!("        instructions: {}\n", indicator);
-    for line in lines {
-        output.push_str(&format!("            {} {}\n", line_prefix, line));
+    DEFAULT_ASSIGNMENT_SYNTAX.to_string()
+}
+
+/// The keyword an assignment syntax starts with (`set`)
+pub fn assignment_keyword(syntax: &str) -> &str {
+    syntax.split(' ').next().unwrap_or_default()
+}
+
+/// Format a `set` clause with the assignment syntax from the rules
+pub fn format_assignment(clause: &SetClause, rules: Option<&ConversionRules>) -> String {
+    get_assignment_syntax(rules)
+        .replace("{variable}", &format!("{}{}", VARIABLE_REFERENCE_PREFIX, clause.variable))
+        .replace("{value}", &format!("{}{}", OUTPUT_REFERENCE_PREFIX, clause.output))
+}
+
+/// Format a const/default input value as an Agent Script literal
+/// Strings are quoted, booleans use the actions boolean format; other values
+/// (null, lists, objects) have no literal form and return None
+pub fn format_literal(value: &serde_json::Value, rules: Option<&ConversionRules>) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(quote(s)),
+        serde_json::Value::Bool(b) => {
+            Some(format_boolean(*b, &get_boolean_format(rules, BooleanBlock::Actions)).to_string())
+        }
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
     }
-    output
+}
+
+/// Get indentation from rules as (base, nested) spaces
+/// `base` indents the fields of top-level blocks, `nested` each deeper level
+pub fn get_indentation(rules: Option<&ConversionRules>) -> (usize, usize) {
+    let mut base = DEFAULT_INDENTATION;
+    let mut nested = DEFAULT_INDENTATION;
+    if let Some(rules) = rules {
+        if let Some(output_format) = &rules.output_format {
+            if let Some(indentation) = &output_format.indentation {
+                if let Some(value) = indentation.base {
+                    base = value as usize;
+                }
+                if let Some(value) = indentation.nested {
+                    nested = value as usize;
+                }
+            }
+        }
+    }
+    (base, nested)
 }
 
 /// Get instruction indicator from rules
-fn get_instruction_indicator(rules: &Option<ConversionRules>) -> String {
+pub fn get_instruction_indicator(rules: Option<&ConversionRules>) -> String {
     if let Some(rules) = rules {
         if let Some(output_format) = &rules.output_format {
             if let Some(reasoning) = &output_format.reasoning {
@@ -181,7 +486,7 @@ fn get_instruction_indicator(rules: &Option<ConversionRules>) -> String {
 }
 
 /// Get instruction line prefix from rules
-fn get_instruction_line_prefix(rules: &Option<ConversionRules>) -> String {
+pub fn get_instruction_line_prefix(rules: Option<&ConversionRules>) -> String {
     if let Some(rules) = rules {
         if let Some(output_format) = &rules.output_format {
             if let Some(reasoning) = &output_format.reasoning {
@@ -196,70 +501,67 @@ fn get_instruction_line_prefix(rules: &Option<ConversionRules>) -> String {
     "|".to_string()
 }
 
-/// Format detailed actions for output
-fn format_detailed_actions(actions: &HashMap<String, Action>, rules: &Option<ConversionRules>) -> String {
-    let mut output = String::new();
-    let mut action_keys: Vec<_> = actions.keys().collect();
-    action_keys.sort();
+/// Write detailed actions
+fn write_detailed_actions(writer: &mut ScriptWriter, actions: &IndexMap<String, Action>, rules: Option<&ConversionRules>) {
+    let booleans = get_boolean_format(rules, BooleanBlock::Actions);
+    let order = get_output_order(rules);
     
-    for action_name in action_keys {
+    for action_name in ordered_keys(actions, order) {
         let action = &actions[action_name];
-        output.push_str(&format!("        {}:\n", action_name));
+        writer.block(2, &key(action_name));
         
         // Description - apply variable conversion
         let desc = convert_variables_in_text(Some(&action.description), rules);
-        output.push_str(&format!("            description: \"{}\"\n", escape_yaml_string(&desc)));
+        writer.string(3, "description", &desc);
         
         // Label
         if let Some(label) = &action.label {
-            output.push_str(&format!("            label: \"{}\"\n", label));
+            writer.string(3, "label", label);
         }
         
         // User confirmation
-        output.push_str(&format!("            require_user_confirmation: {}\n", format_boolean_value(action.require_user_confirmation)));
+        writer.literal(3, "require_user_confirmation", format_boolean(action.require_user_confirmation, &booleans));
         
         // Progress indicator
-        output.push_str(&format!("            include_in_progress_indicator: {}\n", format_boolean_value(action.include_in_progress_indicator)));
+        writer.literal(3, "include_in_progress_indicator", format_boolean(action.include_in_progress_indicator, &booleans));
         
-        // Source - only include if it's a readable name (contains underscores), not a Salesforce ID
+        // Source - only include if it's a readable name, not a Salesforce record ID
         if let Some(source) = &action.source {
-            if is_readable_source_name(source) {
-                output.push_str(&format!("            source: \"{}\"\n", source));
+            if !is_salesforce_id(source) {
+                writer.string(3, "source", source);
             }
         }
         
         // Target
-        output.push_str(&format!("            target: \"{}\"\n", action.target));
+        writer.string(3, "target", &action.target);
         
         // Progress indicator message (optional, after target)
         if let Some(progress_msg) = &action.progress_indicator_message {
-            output.push_str(&format!("            progress_indicator_message: \"{}\"\n", escape_yaml_string(progress_msg)));
+            writer.string(3, "progress_indicator_message", progress_msg);
         }
         
         // Inputs
         if let Some(inputs) = &action.inputs {
             if !inputs.is_empty() {
-                output.push_str("                \n");
-                output.push_str("            inputs:\n");
-                let mut input_keys: Vec<_> = inputs.keys().collect();
-                input_keys.sort();
-                for input_name in input_keys {
+                writer.blank_line();
+                writer.block(3, "inputs");
+                for input_name in ordered_keys(inputs, order) {
                     let input_def = &inputs[input_name];
                     // Quote input names
-                    output.push_str(&format!("                \"{}\": {}\n", input_name, input_def.input_type));
+                    writer.plain(4, &quote(input_name), &input_def.input_type);
                     
                     // Input properties - order: description, label, is_required, is_user_input, complex_data_type_name
                     if let Some(desc) = &input_def.description {
                         let input_desc = convert_variables_in_text(Some(desc), rules);
-                        output.push_str(&format!("                    description: \"{}\"\n", escape_yaml_string(&input_desc)));
+                        writer.string(5, "description", &input_desc);
                     }
                     if let Some(label) = &input_def.label {
-                        output.push_str(&format!("                    label: \"{}\"\n", label));
+                        writer.string(5, "label", label);
                     }
-                    output.push_str(&format!("                    is_required: {}\n", format_boolean_value(input_def.is_required)));
-                    output.push_str(&format!("                    is_user_input: {}\n", format_boolean_value(input_def.is_user_input)));
+                    writer.literal(5, "is_required", format_boolean(input_def.is_required, &booleans));
+                    writer.literal(5, "is_user_input", format_boolean(input_def.is_user_input, &booleans));
                     if let Some(complex_type) = &input_def.complex_data_type_name {
-                        output.push_str(&format!("                    complex_data_type_name: \"{}\"\n", complex_type));
+                        writer.string(5, "complex_data_type_name", complex_type);
                     }
                 }
             }
@@ -268,76 +570,525 @@ fn format_detailed_actions(actions: &HashMap<String, Action>, rules: &Option<Con
         // Outputs
         if let Some(outputs) = &action.outputs {
             if !outputs.is_empty() {
-                output.push_str("                \n");
-                output.push_str("            outputs:\n");
-                let mut output_keys: Vec<_> = outputs.keys().collect();
-                output_keys.sort();
-                for output_name in output_keys {
+                writer.blank_line();
+                writer.block(3, "outputs");
+                for output_name in ordered_keys(outputs, order) {
                     let output_def = &outputs[output_name];
                     // Quote output names
-                    output.push_str(&format!("                \"{}\": {}\n", output_name, output_def.output_type));
+                    writer.plain(4, &quote(output_name), &output_def.output_type);
                     
                     // Output properties - order: description, label, is_displayable, is_used_by_planner, complex_data_type_name
                     if let Some(desc) = &output_def.description {
                         let output_desc = convert_variables_in_text(Some(desc), rules);
-                        output.push_str(&format!("                    description: \"{}\"\n", escape_yaml_string(&output_desc)));
+                        writer.string(5, "description", &output_desc);
                     }
                     if let Some(label) = &output_def.label {
-                        output.push_str(&format!("                    label: \"{}\"\n", label));
+                        writer.string(5, "label", label);
                     }
-                    output.push_str(&format!("                    is_displayable: {}\n", format_boolean_value(output_def.is_displayable)));
-                    output.push_str(&format!("                    is_used_by_planner: {}\n", format_boolean_value(output_def.is_used_by_planner)));
+                    writer.literal(5, "is_displayable", format_boolean(output_def.is_displayable, &booleans));
+                    writer.literal(5, "is_used_by_planner", format_boolean(output_def.is_used_by_planner, &booleans));
                     if let Some(complex_type) = &output_def.complex_data_type_name {
-                        output.push_str(&format!("                    complex_data_type_name: \"{}\"\n", complex_type));
+                        writer.string(5, "complex_data_type_name", complex_type);
                     }
                 }
             }
         }
     }
-    
-    output
 }
 
-/// Check if source is a readable name (API name with underscores) vs a Salesforce ID
-/// Salesforce IDs are typically 15 or 18 alphanumeric characters without underscores
-fn is_readable_source_name(source: &str) -> bool {
-    // If it contains underscores, it's likely an API name
-    if source.contains('_') {
-        return true;
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script_parser::parse_agent_script;
+    use proptest::collection::{hash_map, vec};
+    use proptest::option;
+    use proptest::prelude::*;
+
+    /// Rules that leave text and order untouched, so every scalar should read back verbatim
+    fn verbatim_rules() -> Option<ConversionRules> {
+        crate::parse_rules(r#"{"variable_conversion": {"enabled": false}, "output_format": {"order": "source"}}"#).unwrap()
     }
-    
-    // If it contains spaces, it's a readable name
-    if source.contains(' ') {
-        return true;
+
+    /// Insertion-ordered map with random keys
+    fn index_map<V: std::fmt::Debug>(
+        values: impl Strategy<Value = V>,
+        size: std::ops::Range<usize>,
+    ) -> impl Strategy<Value = IndexMap<String, V>> {
+        vec((text(), values), size).prop_map(|entries| entries.into_iter().collect())
     }
-    
-    // Check if it looks like a Salesforce ID (15 or 18 alphanumeric chars)
-    let len = source.len();
-    if (len == 15 || len == 18) && source.chars().all(|c| c.is_alphanumeric()) {
-        // Additional check: Salesforce IDs typically have a mix of letters and numbers
-        let has_letters = source.chars().any(|c| c.is_alphabetic());
-        let has_numbers = source.chars().any(|c| c.is_numeric());
-        if has_letters && has_numbers {
-            return false; // This looks like a Salesforce ID
-        }
+
+    fn text() -> impl Strategy<Value = String> {
+        prop_oneof![any::<String>(), "[a-z_]{1,8}", "[ -~]{0,12}"]
     }
-    
-    // Default: if it's purely alphanumeric and reasonably short, might be an ID
-    // Check for pattern: starts with numbers or has consecutive digits (common in SF IDs)
-    if source.chars().all(|c| c.is_alphanumeric()) {
-        // Check if it has 3+ consecutive digits (common in Salesforce IDs like "172Wt00000HG6ShIAL")
-        let mut consecutive_digits = 0;
-        for c in source.chars() {
-            if c.is_numeric() {
-                consecutive_digits += 1;
-                if consecutive_digits >= 3 {
-                    return false; // Likely a Salesforce ID
+
+    fn input_def() -> impl Strategy<Value = ActionInputDef> {
+        (text(), option::of(text()), option::of(text()), any::<bool>(), any::<bool>(), option::of(text()))
+            .prop_map(|(input_type, description, label, is_required, is_user_input, complex_data_type_name)| {
+                ActionInputDef {
+                    input_type,
+                    const_value: None,
+                    description,
+                    label,
+                    is_required,
+                    is_user_input,
+                    complex_data_type_name,
+                }
+            })
+    }
+
+    fn output_def() -> impl Strategy<Value = ActionOutputDef> {
+        (text(), option::of(text()), option::of(text()), any::<bool>(), any::<bool>(), option::of(text()))
+            .prop_map(|(output_type, description, label, is_displayable, is_used_by_planner, complex_data_type_name)| {
+                ActionOutputDef {
+                    output_type,
+                    description,
+                    label,
+                    is_displayable,
+                    is_used_by_planner,
+                    complex_data_type_name,
+                }
+            })
+    }
+
+    fn action() -> impl Strategy<Value = Action> {
+        (
+            (text(), option::of(text()), any::<bool>(), any::<bool>(), option::of(text())),
+            (option::of(text()), text()),
+            option::of(index_map(input_def(), 0..3)),
+            option::of(index_map(output_def(), 0..3)),
+        )
+            .prop_map(|((description, label, confirm, progress, progress_message), (source, target), inputs, outputs)| {
+                Action {
+                    description,
+                    label,
+                    require_user_confirmation: confirm,
+                    include_in_progress_indicator: progress,
+                    progress_indicator_message: progress_message,
+                    source,
+                    target,
+                    inputs,
+                    outputs,
+                }
+            })
+    }
+
+    fn with_param() -> impl Strategy<Value = WithParam> {
+        let binding = prop_oneof![
+            Just(WithBinding::SlotFill),
+            "[A-Za-z_][A-Za-z0-9_]{0,10}".prop_map(WithBinding::Variable),
+            text().prop_map(|s| WithBinding::Literal(serde_json::Value::String(s))),
+            any::<bool>().prop_map(|b| WithBinding::Literal(serde_json::Value::Bool(b))),
+            any::<i64>().prop_map(|n| WithBinding::Literal(serde_json::Value::from(n))),
+        ];
+        (text(), binding).prop_map(|(name, binding)| WithParam { name, binding })
+    }
+
+    fn set_clause() -> impl Strategy<Value = SetClause> {
+        ("[A-Za-z_][A-Za-z0-9_]{0,10}", "[A-Za-z_][A-Za-z0-9_]{0,10}")
+            .prop_map(|(variable, output)| SetClause { variable, output })
+    }
+
+    fn reasoning_action() -> impl Strategy<Value = ReasoningAction> {
+        let condition = "@variables\\.[A-Za-z_][A-Za-z0-9_]{0,10} (==|!=|>) (True|False|[0-9]{1,3})";
+        let comment = "[!-~]( ?[!-~]){0,10}";
+        (
+            (text(), option::of(text())),
+            (option::of(vec(with_param(), 1..3)), option::of(vec(set_clause(), 1..3))),
+            (option::of(condition), option::of(vec(comment, 1..3))),
+        )
+            .prop_map(|((target, description), (with_params, set_clauses), (condition, comments))| {
+                ReasoningAction { target, description, with_params, set_clauses, condition, comments }
+            })
+    }
+
+    fn topic() -> impl Strategy<Value = Topic> {
+        (
+            text(),
+            text(),
+            option::of(text()),
+            text(),
+            option::of(index_map(reasoning_action(), 0..3)),
+            option::of(index_map(action(), 0..2)),
+        )
+            .prop_map(|(label, description, classification_description, instructions, reasoning_actions, actions)| Topic {
+                label,
+                description,
+                classification_description,
+                reasoning: ReasoningSection { instructions, actions: reasoning_actions },
+                actions,
+            })
+    }
+
+    fn variable() -> impl Strategy<Value = Variable> {
+        (text(), option::of(text()), option::of(text()), text())
+            .prop_map(|(var_type, label, source, description)| Variable { var_type, label, source, description })
+    }
+
+    fn nga_output() -> impl Strategy<Value = NGAOutput> {
+        (
+            (text(), text(), text()),
+            (text(), text(), text(), text(), text()),
+            index_map(variable(), 0..3),
+            (text(), text(), any::<bool>(), text(), any::<bool>()),
+            option::of((text(), any::<bool>())),
+            hash_map((prop_oneof![Just("topic "), Just("start_agent ")], text()), topic(), 0..3),
+        )
+            .prop_map(|(system, config, variables, settings, connection, topics)| NGAOutput {
+                system: SystemSection {
+                    instructions: system.0,
+                    messages: MessagesSection { welcome: system.1, error: system.2 },
+                },
+                config: ConfigSection {
+                    default_agent_user: config.0,
+                    agent_label: config.1,
+                    developer_name: config.2,
+                    agent_type: config.3,
+                    description: config.4,
+                },
+                topics: topics
+                    .into_iter()
+                    .map(|((prefix, name), topic)| (format!("{}{}", prefix, name), topic))
+                    .collect(),
+                variables,
+                language: LanguageSection {
+                    default_locale: settings.0,
+                    additional_locales: settings.1,
+                    all_additional_locales: settings.2,
+                },
+                knowledge: KnowledgeSection {
+                    rag_feature_config_id: settings.3,
+                    citations_enabled: settings.4,
+                },
+                connections: connection
+                    .into_iter()
+                    .map(|(name, allowed)| {
+                        (format!("connection {}", name), ConnectionSection { adaptive_response_allowed: allowed })
+                    })
+                    .collect(),
+            })
+    }
+
+    /// Check that every scalar written as a key or quoted/plain value reads back verbatim
+    fn assert_scalars_round_trip(original: &NGAOutput, parsed: &NGAOutput) {
+        assert_eq!(parsed.system.instructions, original.system.instructions);
+        assert_eq!(parsed.system.messages.welcome, original.system.messages.welcome);
+        assert_eq!(parsed.system.messages.error, original.system.messages.error);
+        assert_eq!(parsed.config.default_agent_user, original.config.default_agent_user);
+        assert_eq!(parsed.config.agent_label, original.config.agent_label);
+        assert_eq!(parsed.config.developer_name, original.config.developer_name);
+        assert_eq!(parsed.config.agent_type, original.config.agent_type);
+        assert_eq!(parsed.config.description, original.config.description);
+        assert_eq!(parsed.language.default_locale, original.language.default_locale);
+        assert_eq!(parsed.language.additional_locales, original.language.additional_locales);
+        assert_eq!(parsed.knowledge.rag_feature_config_id, original.knowledge.rag_feature_config_id);
+
+        for (name, variable) in &original.variables {
+            let parsed_variable = &parsed.variables[name];
+            assert_eq!(parsed_variable.var_type, variable.var_type);
+            assert_eq!(parsed_variable.label, variable.label);
+            assert_eq!(parsed_variable.description, variable.description);
+        }
+
+        for (key, topic) in &original.topics {
+            let parsed_topic = &parsed.topics[key];
+            assert_eq!(parsed_topic.label, topic.label);
+            assert_eq!(parsed_topic.description, topic.description);
+            assert_eq!(parsed_topic.classification_description, topic.classification_description);
+
+            for (name, action) in topic.reasoning.actions.iter().flatten() {
+                let parsed_action = &parsed_topic.reasoning.actions.as_ref().unwrap()[name];
+                assert_eq!(parsed_action.target, action.target);
+                assert_eq!(parsed_action.description, action.description);
+                assert_eq!(parsed_action.with_params, action.with_params);
+                assert_eq!(parsed_action.set_clauses, action.set_clauses);
+                assert_eq!(parsed_action.condition, action.condition);
+                assert_eq!(parsed_action.comments, action.comments);
+            }
+
+            for (name, action) in topic.actions.iter().flatten() {
+                let parsed_action = &parsed_topic.actions.as_ref().unwrap()[name];
+                assert_eq!(parsed_action.description, action.description);
+                assert_eq!(parsed_action.label, action.label);
+                assert_eq!(parsed_action.target, action.target);
+                assert_eq!(parsed_action.progress_indicator_message, action.progress_indicator_message);
+
+                for (input_name, input) in action.inputs.iter().flatten() {
+                    let parsed_input = &parsed_action.inputs.as_ref().unwrap()[input_name];
+                    assert_eq!(parsed_input.input_type, input.input_type);
+                    assert_eq!(parsed_input.description, input.description);
+                    assert_eq!(parsed_input.label, input.label);
+                    assert_eq!(parsed_input.complex_data_type_name, input.complex_data_type_name);
+                }
+                for (output_name, output) in action.outputs.iter().flatten() {
+                    let parsed_output = &parsed_action.outputs.as_ref().unwrap()[output_name];
+                    assert_eq!(parsed_output.output_type, output.output_type);
+                    assert_eq!(parsed_output.description, output.description);
+                    assert_eq!(parsed_output.label, output.label);
+                    assert_eq!(parsed_output.complex_data_type_name, output.complex_data_type_name);
                 }
-            } else {
-                consecutive_digits = 0;
             }
         }
     }
-    
-    true
+
+    #[test]
+    fn test_special_characters_are_quoted() {
+        let rules = verbatim_rules();
+        let mut nga = parse_agent_script("config:\n  agent_label: \"x\"\n", rules.as_ref()).unwrap();
+        nga.config.agent_label = "Say \"hi\"\nthen leave".to_string();
+        nga.config.default_agent_user = "bot\\user".to_string();
+
+        let script = generate_nga_yaml(&nga, rules.as_ref());
+        assert!(script.contains("  agent_label: \"Say \\\"hi\\\"\\nthen leave\"\n"));
+        assert!(script.contains("  default_agent_user: \"bot\\\\user\"\n"));
+        assert_scalars_round_trip(&nga, &parse_agent_script(&script, rules.as_ref()).unwrap());
+    }
+
+    /// An agent with optional blocks, a const input, and topics, actions and inputs listed
+    /// out of alphabetical order, in the default layout
+    const AGENT_SCRIPT: &str = concat!(
+        "language:\n",
+        "    default_locale: \"en_US\"\n",
+        "    additional_locales: \"fr\"\n",
+        "    all_additional_locales: False\n",
+        "\n",
+        "knowledge:\n",
+        "    rag_feature_config_id: \"Orders_Library\"\n",
+        "    citations_enabled: False\n",
+        "\n",
+        "connection messaging:\n",
+        "    adaptive_response_allowed: True\n",
+        "\n",
+        "start_agent topic_selector:\n",
+        "    label: \"Topic Selector\"\n",
+        "\n",
+        "topic orders:\n",
+        "    label: \"Orders\"\n",
+        "\n",
+        "    description: \"Order questions\"\n",
+        "\n",
+        "    reasoning:\n",
+        "        instructions: ->\n",
+        "            | Help with orders.\n",
+        "        actions:\n",
+        "            GetOrder: @actions.GetOrder\n",
+        "                with orderId = ...\n",
+        "                with channel = \"web\"\n",
+        "            CancelOrder: @actions.CancelOrder\n",
+        "\n",
+        "    actions:\n",
+        "        GetOrder:\n",
+        "            description: \"Look up an order\"\n",
+        "            require_user_confirmation: False\n",
+        "            include_in_progress_indicator: False\n",
+        "            target: \"flow://Get_Order\"\n",
+        "\n",
+        "            inputs:\n",
+        "                \"orderId\": string\n",
+        "                    label: \"orderId\"\n",
+        "                    is_required: True\n",
+        "                    is_user_input: True\n",
+        "                \"channel\": string\n",
+        "                    label: \"channel\"\n",
+        "                    is_required: False\n",
+        "                    is_user_input: False\n",
+        "        CancelOrder:\n",
+        "            description: \"Cancel an order\"\n",
+        "            require_user_confirmation: True\n",
+        "            include_in_progress_indicator: False\n",
+        "            target: \"flow://Cancel_Order\"\n",
+        "\n",
+        "topic billing:\n",
+        "    label: \"Billing\"\n",
+    );
+
+    #[test]
+    fn test_indentation_from_rules() {
+        let rules = crate::parse_rules(r#"{"output_format": {"indentation": {"base": 2, "nested": 3}}}"#).unwrap();
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let script = generate_nga_yaml(&nga, rules.as_ref());
+
+        assert!(script.contains("\n  agent_label: \"\"\n"));
+        assert!(script.contains("\n  reasoning:\n     instructions: ->\n        | Help with orders.\n"));
+        assert!(script.contains("\n        GetOrder: @actions.GetOrder\n           with channel = \"web\"\n"));
+        assert!(script.contains("\n           \"orderId\": string\n              label: \"orderId\"\n"));
+
+        // Same script under the default indentation
+        let parsed = parse_agent_script(&script, rules.as_ref()).unwrap();
+        assert_eq!(generate_nga_yaml(&parsed, None), generate_nga_yaml(&nga, None));
+    }
+
+    #[test]
+    fn test_default_indentation_is_consistent() {
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let script = generate_nga_yaml(&nga, None);
+
+        assert!(script.contains("\n    agent_label: \"\"\n"));
+        assert!(!script.contains(" \n"));
+        for line in script.lines() {
+            let indent = line.len() - line.trim_start().len();
+            assert_eq!(indent % 4, 0, "inconsistent indentation: {:?}", line);
+        }
+    }
+
+    /// Top-level block keys of a script, in order
+    fn top_level_keys(script: &str) -> Vec<&str> {
+        script
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with(' '))
+            .map(|line| line.trim_end_matches(':'))
+            .collect()
+    }
+
+    #[test]
+    fn test_all_connections_are_written() {
+        let mut nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        nga.connections.insert("connection voice".to_string(), ConnectionSection { adaptive_response_allowed: false });
+        let script = generate_nga_yaml(&nga, None);
+
+        assert!(script.contains("\nconnection messaging:\n    adaptive_response_allowed: True\n"));
+        assert!(script.contains("\nconnection voice:\n    adaptive_response_allowed: False\n"));
+        assert_eq!(parse_agent_script(&script, None).unwrap().connections.len(), 2);
+    }
+
+    #[test]
+    fn test_block_order_from_rules() {
+        let rules = crate::parse_rules(r#"{"blocks": {
+            "required": ["system", "config", "variables"],
+            "order": ["config", "system", "topic", "start_agent", "variables", "unknown"]
+        }}"#).unwrap();
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let script = generate_nga_yaml(&nga, rules.as_ref());
+
+        // Listed blocks first, then unlisted non-empty ones
+        assert_eq!(top_level_keys(&script), [
+            "config", "system", "topic billing", "topic orders", "start_agent topic_selector", "variables",
+            "language", "knowledge", "connection messaging",
+        ]);
+
+        // Required blocks are written even when empty
+        assert!(script.contains("\nvariables:\n"));
+        let parsed = parse_agent_script(&script, rules.as_ref()).unwrap();
+        assert!(parsed.variables.is_empty());
+        assert_eq!(generate_nga_yaml(&parsed, rules.as_ref()), script);
+    }
+
+    #[test]
+    fn test_default_block_order_omits_empty_optional_blocks() {
+        let mut nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        assert_eq!(top_level_keys(&generate_nga_yaml(&nga, None)), [
+            "system", "config", "language", "knowledge", "connection messaging",
+            "start_agent topic_selector", "topic billing", "topic orders",
+        ]);
+
+        nga.knowledge.rag_feature_config_id.clear();
+        nga.connections.clear();
+        assert_eq!(top_level_keys(&generate_nga_yaml(&nga, None)), [
+            "system", "config", "language", "start_agent topic_selector", "topic billing", "topic orders",
+        ]);
+    }
+
+    #[test]
+    fn test_topic_order_source() {
+        let rules = crate::parse_rules(r#"{"blocks": {"topic_order": "source"}}"#).unwrap();
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+
+        let topics_in = |script: &str| -> Vec<String> {
+            top_level_keys(script).into_iter().filter(|k| k.starts_with("topic ")).map(String::from).collect()
+        };
+        assert_eq!(topics_in(&generate_nga_yaml(&nga, rules.as_ref())), ["topic orders", "topic billing"]);
+        assert_eq!(topics_in(&generate_nga_yaml(&nga, None)), ["topic billing", "topic orders"]);
+    }
+
+    #[test]
+    fn test_output_order_source() {
+        let rules = crate::parse_rules(r#"{"output_format": {"order": "source"}}"#).unwrap();
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let before = |script: &str, first: &str, second: &str| script.find(first).unwrap() < script.find(second).unwrap();
+
+        // Topics, actions, with clauses and inputs in source order
+        let script = generate_nga_yaml(&nga, rules.as_ref());
+        assert!(before(&script, "topic orders:", "topic billing:"));
+        assert!(before(&script, "GetOrder: @actions.GetOrder", "CancelOrder: @actions.CancelOrder"));
+        assert!(before(&script, "with orderId", "with channel"));
+        assert!(before(&script, "        GetOrder:\n", "        CancelOrder:\n"));
+        assert!(before(&script, "\"orderId\": string", "\"channel\": string"));
+        assert_eq!(generate_nga_yaml(&parse_agent_script(&script, rules.as_ref()).unwrap(), rules.as_ref()), script);
+
+        // Alphabetical output is unchanged by the ordered model
+        let script = generate_nga_yaml(&nga, None);
+        assert!(before(&script, "topic billing:", "topic orders:"));
+        assert!(before(&script, "CancelOrder: @actions.CancelOrder", "GetOrder: @actions.GetOrder"));
+        assert!(before(&script, "with channel", "with orderId"));
+        assert!(before(&script, "        CancelOrder:\n", "        GetOrder:\n"));
+        assert!(before(&script, "\"channel\": string", "\"orderId\": string"));
+    }
+
+    #[test]
+    fn test_const_inputs_bound_in_with_clauses() {
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let inputs = nga.topics["topic orders"].actions.as_ref().unwrap()["GetOrder"].inputs.as_ref().unwrap();
+        assert_eq!(inputs["channel"].const_value, Some(serde_json::json!("web")));
+        assert_eq!(inputs["orderId"].const_value, None);
+
+        let script = generate_nga_yaml(&nga, None);
+        assert!(script.contains("\n                with channel = \"web\"\n"));
+        assert!(script.contains("\n                with orderId = ...\n"));
+
+        let rules = crate::parse_rules(r#"{"output_format": {
+            "action_definition": {"boolean_format": {"true": "Yes", "false": "No"}}
+        }}"#).unwrap();
+        assert_eq!(format_literal(&serde_json::json!(false), rules.as_ref()).as_deref(), Some("No"));
+        assert_eq!(format_literal(&serde_json::json!(2.5), None).as_deref(), Some("2.5"));
+        assert_eq!(format_literal(&serde_json::json!(["a"]), None), None);
+    }
+
+    #[test]
+    fn test_boolean_format_overrides() {
+        let rules = crate::parse_rules(r#"{"output_format": {
+            "action_definition": {"boolean_format": {"true": "Yes", "false": "No"}},
+            "language": {"boolean_format": {"true": "true", "false": "false"}},
+            "knowledge": {"boolean_format": {"false": "false"}}
+        }}"#).unwrap();
+
+        assert_eq!(get_boolean_format(rules.as_ref(), BooleanBlock::Actions), ("Yes".to_string(), "No".to_string()));
+        assert_eq!(get_boolean_format(rules.as_ref(), BooleanBlock::Language), ("true".to_string(), "false".to_string()));
+        assert_eq!(get_boolean_format(rules.as_ref(), BooleanBlock::Knowledge), ("Yes".to_string(), "false".to_string()));
+        assert_eq!(get_boolean_format(rules.as_ref(), BooleanBlock::Connection), ("Yes".to_string(), "No".to_string()));
+        assert_eq!(get_boolean_format(None, BooleanBlock::Language), (YAML_TRUE.to_string(), YAML_FALSE.to_string()));
+
+        let nga = parse_agent_script(AGENT_SCRIPT, None).unwrap();
+        let script = generate_nga_yaml(&nga, rules.as_ref());
+        assert!(script.contains("\n    all_additional_locales: false\n"));
+        assert!(script.contains("\n    citations_enabled: false\n"));
+        assert!(script.contains("\n    adaptive_response_allowed: Yes\n"));
+        assert!(script.contains("\n            require_user_confirmation: No\n"));
+        assert!(script.contains("\n                    is_required: Yes\n"));
+
+        let parsed = parse_agent_script(&script, rules.as_ref()).unwrap();
+        assert_eq!(generate_nga_yaml(&parsed, rules.as_ref()), script);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_generated_script_reparses(nga in nga_output()) {
+            let rules = verbatim_rules();
+            let script = generate_nga_yaml(&nga, rules.as_ref());
+            let parsed = parse_agent_script(&script, rules.as_ref())
+                .unwrap_or_else(|e| panic!("generated script does not re-parse: {}\n{}", e, script));
+
+            assert_scalars_round_trip(&nga, &parsed);
+            prop_assert_eq!(generate_nga_yaml(&parsed, rules.as_ref()), script);
+        }
+
+        #[test]
+        fn prop_generated_script_reparses_with_default_rules(nga in nga_output()) {
+            let script = generate_nga_yaml(&nga, None);
+            let parsed = parse_agent_script(&script, None);
+            prop_assert!(parsed.is_ok(), "generated script does not re-parse: {:?}\n{}", parsed.err(), script);
+        }
+    }
 }
@@ -1041,7 +1041,9 @@ async function performConversion() {
                     actionCount: resultObj.action_count || 0,
                     hasVariablesWithDollar: hasVariablesWithDollar,
                     alertMessage: resultObj.alert_message || '',
-                    statusSuffix: resultObj.status_suffix || ''
+                    statusSuffix: resultObj.status_suffix || '',
+                    warnings: resultObj.warnings || [],
+                    renames: resultObj.renames || []
                 }
             };
             
@@ -1051,7 +1053,8 @@ async function performConversion() {
             return;
         } catch (wasmError) {
             console.error('WASM conversion error:', wasmError);
-            setStatus(`Error: ${wasmError.message || 'WASM conversion failed'}`, 'error');
+            const errorPath = wasmError.path ? ` (at ${wasmError.path})` : '';
+            setStatus(`Error: ${wasmError.message || 'WASM conversion failed'}${errorPath}`, 'error');
             showToast('⚠️ Conversion failed. Please check the console for details.');
             outputYaml.value = '';
             updateLineNumbers(outputYaml, outputLineNumbers);
@@ -1300,7 +1303,9 @@ async function generateReport(data) {
             action_count: metadata.actionCount,
             has_variables_with_dollar: metadata.hasVariablesWithDollar,
             alert_message: metadata.alertMessage || null,
-            status_suffix: metadata.statusSuffix || null
+            status_suffix: metadata.statusSuffix || null,
+            warnings: metadata.warnings || [],
+            renames: metadata.renames || []
         });
         
         // Call WASM to generate report data (IP protected)
@@ -1373,6 +1378,9 @@ function formatReportAsMarkdown(reportData, metadata) {
             report.push(`### ${index + 1}. ${topic.label} ${topic.is_start ? '(Start Topic)' : ''}`);
             report.push('');
             report.push(`**Topic Name:** \`${topic.name}\``);
+            if (topic.original_name) {
+                report.push(`**Original Name:** \`${topic.original_name}\``);
+            }
             report.push('');
             report.push('**Description:**');
             report.push(topic.description);
@@ -1383,6 +1391,9 @@ function formatReportAsMarkdown(reportData, metadata) {
                 report.push('');
                 topic.actions.forEach((action, actionIndex) => {
                     report.push(`${actionIndex + 1}. **${action.label}** (\`${action.name}\`)`);
+                    if (action.original_name) {
+                        report.push(`   - **Original Name:** \`${action.original_name}\``);
+                    }
                     report.push(`   - **Target:** ${action.target}`);
                     report.push(`   - **Type:** ${action.action_type}`);
                     report.push(`   - **Description:** ${action.description}`);